@@ -4,7 +4,9 @@ use syn::{ItemStruct, NestedMeta};
 
 struct StructPrototype {
     name:  String,
-    fields: Vec<Field>
+    generics: syn::Generics,
+    fields: Vec<Field>,
+    attributes: Vec<Attribute>
 }
 
 impl StructPrototype {
@@ -12,16 +14,73 @@ impl StructPrototype {
         let struct_tokens: ItemStruct = syn::parse(tokens).ok()?;
 
         let name = struct_tokens.ident.to_string();
+        let generics = struct_tokens.generics.clone();
 
+        let attributes = collect_attrs(&struct_tokens.attrs);
         let mut fields: Vec<Field> = vec![];
 
+        /* an endian on the struct applies to fields without their own */
+        let default_endian = attributes.iter()
+            .find(|attr| matches!(attr, Attribute::Endian(_)));
+
         for f in &struct_tokens.fields {
-            if let Some(ff) = Field::from_tok(f) {
+            if let Some(mut ff) = Field::from_tok(f) {
+                if let Some(endian) = default_endian {
+                    if !ff.attributes.iter().any(|attr| matches!(attr, Attribute::Endian(_))) {
+                        ff.attributes.push(endian.clone());
+                    }
+                }
                 fields.push(ff);
             }
         }
 
-        Some(StructPrototype { name, fields })
+        /* a trailing byte may be absent, so nothing can come after it */
+        if let Some(field) = fields.iter().rev().skip(1).find(|f| f.is_trailing()) {
+            panic!("trailing field `{}` of {} must be the last field", field.name, name);
+        }
+
+        Some(StructPrototype { name, generics, fields, attributes })
+    }
+
+    /* 
+     * (checksum field, first field, end field) of every `checksum_over`, the
+     * range must name fields in order and not include the checksum itself
+     */
+    fn checksums(&self) -> Vec<(syn::Ident, syn::Ident, syn::Ident)> {
+        let position = |name: &str| self.fields.iter().position(|f| f.name == name)
+            .unwrap_or_else(|| panic!("checksum_over names unknown field `{}` of {}", name, self.name));
+
+        self.fields.iter().enumerate().filter_map(|(idx, field)| {
+            let (start, end) = field.checksum_over()?;
+            let (first, last) = (position(start), position(end));
+            if first >= last || (first..last).contains(&idx) {
+                panic!("checksum_over of `{}` must be an ordered range excluding itself", field.name);
+            }
+            Some((offset_ident(&field.name), offset_ident(start), offset_ident(end)))
+        }).collect()
+    }
+
+    /* the wire size asserted with `#[bytes_serialize(size = N)]` */
+    fn expected_size(&self) -> Option<usize> {
+        self.attributes.iter().find_map(|attr| match attr {
+            Attribute::Size(value) => Some(*value),
+            _ => None
+        })
+    }
+
+    /* `impl<..> #trait for Struct<..>`, for traits without lifetime */
+    fn impl_header(&self, trait_name: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let struct_name = format_ident!("{}", self.name);
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        quote! { impl #impl_generics #trait_name for #struct_name #ty_generics #where_clause }
+    }
+
+    /* 
+     * the lifetime deserialized values borrow from, this is the lifetime of
+     * the struct if it has one
+     */
+    fn borrow_lifetime(&self) -> Option<syn::Lifetime> {
+        self.generics.lifetimes().next().map(|l| l.lifetime.clone())
     }
 }
 
@@ -34,7 +93,7 @@ struct Field {
 impl Field {
     fn from_tok(field: &syn::Field) -> Option<Field>
     {
-        let attributes = collect_attrs(field);
+        let attributes = collect_attrs(&field.attrs);
         let name = field.ident.as_ref().map(|s| s.to_string())?;
         let tpe  = field.ty.clone();
         Some(Field { name, tpe, attributes })
@@ -43,9 +102,54 @@ impl Field {
     fn as_int_primitive(&self) -> Option<(usize, String, String)> {
         endian_spec(self.tpe.clone(), &self.attributes)
     }
+
+    /* size known without the value, for bytes, integers and byte arrays */
+    fn fixed_size(&self) -> Option<proc_macro2::TokenStream> {
+        if self.reserved().is_some() {
+            return Some(quote! { 1 });
+        }
+        if let Some((size, _t, _e)) = self.as_int_primitive() {
+            return Some(quote! { #size });
+        }
+        match &self.tpe {
+            syn::Type::Array(arr) if is_byte(&arr.elem) => {
+                let len = &arr.len;
+                Some(quote! { #len })
+            },
+            tpe if is_byte(tpe) => Some(quote! { 1 }),
+            _ => None
+        }
+    }
+
+    fn checksum_over(&self) -> Option<(&str, &str)> {
+        self.attributes.iter().find_map(|attr| match attr {
+            Attribute::ChecksumOver(start, end) => Some((start.as_str(), end.as_str())),
+            _ => None
+        })
+    }
+
+    fn is_trailing(&self) -> bool {
+        self.attributes.iter().any(|attr| matches!(attr, Attribute::Trailing))
+    }
+
+    fn is_len_prefixed(&self) -> bool {
+        self.attributes.iter().any(|attr| matches!(attr, Attribute::LenPrefix(_)))
+    }
+
+    /* the constant of a `reserved = N` byte */
+    fn reserved(&self) -> Option<u8> {
+        self.attributes.iter().find_map(|attr| match attr {
+            Attribute::Reserved(value) => Some(*value),
+            _ => None
+        })
+    }
+}
+
+/* local holding the byte offset a field starts at, for checksums */
+fn offset_ident(field: &str) -> syn::Ident {
+    format_ident!("__offset_{}", field)
 }
 
-#[allow(dead_code)]
 fn typename(tpe: &syn::Type) -> Option<String> {
     if let syn::Type::Path(tp) = tpe {
         tp.path.get_ident().map(|i| i.to_string())
@@ -57,18 +161,29 @@ fn typename(tpe: &syn::Type) -> Option<String> {
 #[allow(dead_code)]
 fn is_int(tpe: &syn::Type) -> bool {
     typename(tpe).map(|t| 
-    match t.as_str() {
-        "i16" | "u16" | "u32" | "i32" | "u64" | "i64" | "i128" | "u128" =>
-            true,
-        _ => 
-            false
-    }).unwrap_or(false)
+        matches!(t.as_str(),
+            "i16" | "u16" | "u32" | "i32" | "u64" | "i64" | "i128" | "u128")
+    ).unwrap_or(false)
 }
 
 
+fn is_byte(tpe: &syn::Type) -> bool {
+    typename(tpe).is_some_and(|t| t == "u8" || t == "i8")
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 enum Attribute {
-    Endian(String)
+    Endian(String),
+    /* a byte always written as the constant and ignored when not strict */
+    Reserved(u8),
+    /* on the struct, the wire size to check at compile time */
+    Size(usize),
+    /* a byte slice preceded by its length, the value is the length type */
+    LenPrefix(String),
+    /* a checksum byte over the fields from the first up to, not including, the second */
+    ChecksumOver(String, String),
+    /* an `Option<u8>` last field, absent when the payload ends before it */
+    Trailing
 }
 
 fn endian_spec(tpe: syn::Type, attrs: &[Attribute]) 
@@ -78,7 +193,6 @@ fn endian_spec(tpe: syn::Type, attrs: &[Attribute])
     let endian = {
         let mut res = None;
         'next: for attr in attrs {
-            #[allow(irrefutable_let_patterns)]
             if let Attribute::Endian(value) = attr {
                 res = Some(value);
                 break 'next;
@@ -100,28 +214,46 @@ fn endian_spec(tpe: syn::Type, attrs: &[Attribute])
     }
 }
 
-fn collect_attrs(field: &syn::Field) -> Vec<Attribute> {
+fn collect_attrs(attrs: &[syn::Attribute]) -> Vec<Attribute> {
     let mut v = Vec::new();
 
     /* THE GOLDEN TRIANGLE !!!! */
-    for attr in &field.attrs {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("bytes_serialize")) {
         if let Ok(syn::Meta::List(meta_list)) = attr.parse_meta() {
             for attribute in meta_list.nested.iter() {
+                if let NestedMeta::Meta(syn::Meta::Path(path)) = attribute {
+                    if path.is_ident("trailing") {
+                        v.push(Attribute::Trailing);
+                    }
+                }
                 if let NestedMeta::Meta(syn::Meta::NameValue(nv)) = attribute {
                     if let Some(key) = nv.path.get_ident().map(|s| s.to_string()) {
-                        if let syn::Lit::Str(litv) = &nv.lit {
-                            let val = litv.value();
-                            match key.as_str() {
-                                "endian" => {
-                                    match val.as_str() {
-                                        "le" | "be" => { 
-                                            v.push(Attribute::Endian(val));
-                                        },
-                                        _    => ()
-                                    }
-                                },
-                                _ => ()
-                            }
+                        match &nv.lit {
+                            syn::Lit::Str(litv) => {
+                                let val = litv.value();
+                                match (key.as_str(), val.as_str()) {
+                                    ("endian", "le" | "be") => v.push(Attribute::Endian(val)),
+                                    ("len_prefix", "u8") => v.push(Attribute::LenPrefix(val)),
+                                    ("len_prefix", _) => panic!("only u8 length prefixes are supported"),
+                                    ("checksum_over", range) => {
+                                        let (start, end) = range.split_once("..")
+                                            .expect("checksum_over must be a `start..end` range of fields");
+                                        v.push(Attribute::ChecksumOver(start.trim().to_string(), end.trim().to_string()));
+                                    },
+                                    _ => {}
+                                }
+                            },
+                            syn::Lit::Int(litv) if key == "reserved" => {
+                                let val = litv.base10_parse::<u8>()
+                                    .expect("reserved value must fit in a u8");
+                                v.push(Attribute::Reserved(val));
+                            },
+                            syn::Lit::Int(litv) if key == "size" => {
+                                let val = litv.base10_parse::<usize>()
+                                    .expect("size must be an integer");
+                                v.push(Attribute::Size(val));
+                            },
+                            _ => {}
                         }
                     }
                 }
@@ -132,26 +264,55 @@ fn collect_attrs(field: &syn::Field) -> Vec<Attribute> {
     v
 }
 
-#[proc_macro_derive(BytesSerializationSized)]
+#[proc_macro_derive(BytesSerializationSized, attributes(bytes_serialize))]
 pub fn derive_serialization_sized(tokens: TokenStream) -> TokenStream {
     let s = StructPrototype::from_tok_stream(tokens).expect("can only apply to struct");
 
     let sizes = s.fields.iter().map(|field| {
         let name = format_ident!("{}", &field.name);
-        match field.as_int_primitive() {
-            None => quote! { self.#name.size() },
-            Some((size, _t, _e)) => quote ! { #size }
+        if field.is_len_prefixed() {
+            return quote! { (1 + self.#name.len()) };
+        }
+        if field.is_trailing() {
+            return quote! { usize::from(self.#name.is_some()) };
+        }
+        match (field.reserved(), field.as_int_primitive()) {
+            (Some(_), _) => quote! { 1 },
+            (None, None) => quote! { self.#name.size() },
+            (None, Some((size, _t, _e))) => quote ! { #size }
         }
     });
 
-    let struct_name = format_ident!("{}", s.name);
+    let header = s.impl_header(quote! { BytesSerializationSized });
+
+    /* fail the build when the fields no longer add up to the asserted size */
+    let size_check = s.expected_size().map(|expected| {
+        let fixed = s.fields.iter()
+            .map(|field| field.fixed_size().ok_or(&field.name))
+            .collect::<Result<Vec<_>, _>>();
+
+        match fixed {
+            Ok(sizes) => {
+                let msg = format!("wire size of {} is not {}", s.name, expected);
+                quote! {
+                    const _: () = assert!(0 #(+ #sizes)* == #expected, #msg);
+                }
+            },
+            Err(name) => {
+                let msg = format!("size of field `{}` of {} is not known at compile time", name, s.name);
+                quote! { compile_error!(#msg); }
+            }
+        }
+    });
 
     (quote! {
-        impl BytesSerializationSized for #struct_name {
+        #header {
             fn size(&self) -> usize {
                 0 #(+ #sizes)*
             }
         }
+
+        #size_check
     }).into()
 }
 
@@ -163,46 +324,104 @@ pub fn derive_deserializable(tokens: TokenStream) -> TokenStream {
 
     let read = s.fields.iter().map(|field| {
         let name = format_ident!("{}", &field.name);
+        let offset = offset_ident(&field.name);
         let tpe  = &field.tpe;
-        match field.as_int_primitive() {
-            None => quote! {
-                let #name = summon_from_bytes::<#tpe>(bytes, strict)?;
-                bytes = &bytes[#name.size()..];
-            },
-            Some((size, tpe, e)) => {
-                let func = format_ident!("from_{}_bytes", e);
-                let tpei = format_ident!("{}", tpe);
+        let read = field_read(field, &name, tpe);
+        quote! {
+            let #offset = slice.len() - bytes.len();
+            #read
+        }
+    });
 
-                quote! {
-                    if bytes.len() < #size {
-                        return Err(Error::OutBufferTooSmall);
-                    }
-                    let #name = #tpei::#func(bytes.try_into().unwrap());
-                    bytes = &bytes[#size..];
-                }
-            }
+    let verify = s.checksums().into_iter().map(|(checksum, start, end)| quote! {
+        let sum = slice[#start..#end].iter().fold(slice[#checksum], |acc, b| acc.wrapping_add(*b));
+        if sum != 0 {
+            return Err(Error::InvalidChecksum);
         }
     });
 
-    let struct_name = format_ident!("{}", s.name);
+    let header = match s.borrow_lifetime() {
+        Some(lt) => s.impl_header(quote! { BytesDeserializable<#lt> }),
+        None => {
+            let struct_name = format_ident!("{}", s.name);
+            quote! { impl<'a> BytesDeserializable<'a> for #struct_name }
+        }
+    };
 
-    let ret = (quote! {
-        impl<'a> BytesDeserializable<'a> for #struct_name {
-            fn from_bytes(slice: &'a [u8], strict: bool) -> Result<#struct_name, Error>
+    let lt = s.borrow_lifetime()
+        .unwrap_or_else(|| syn::Lifetime::new("'a", proc_macro2::Span::call_site()));
+
+    (quote! {
+        #header {
+            fn from_bytes(slice: &#lt [u8], strict: bool) -> Result<Self, Error>
             {
                 let mut bytes = slice;
 
                 #(#read ;)*
 
-                Ok(#struct_name {
+                #(#verify)*
+
+                Ok(Self {
                     #(#field_names ,)*
                 })
             }
         }
         
-    }).into();
+    }).into()
+}
 
-    ret
+/* parse one field off the front of `bytes` into a local of the field name */
+fn field_read(field: &Field, name: &syn::Ident, tpe: &syn::Type) -> proc_macro2::TokenStream {
+    if let Some(value) = field.reserved() {
+        return quote! {
+            let Some((&byte, rest)) = bytes.split_first() else {
+                return Err(Error::PayloadTooSmall);
+            };
+            if strict && byte != #value {
+                return Err(Error::InvalidConfiguration);
+            }
+            let #name = #value;
+            bytes = rest;
+        };
+    }
+    if field.is_len_prefixed() {
+        return quote! {
+            let Some((&len, rest)) = bytes.split_first() else {
+                return Err(Error::PayloadTooSmall);
+            };
+            /* the declared length must fit in what is left */
+            if rest.len() < usize::from(len) {
+                return Err(Error::PayloadTooSmall);
+            }
+            let (#name, rest) = rest.split_at(usize::from(len));
+            bytes = rest;
+        };
+    }
+    if field.is_trailing() {
+        return quote! {
+            let #name = bytes.first().copied();
+            bytes = bytes.get(usize::from(#name.is_some())..).unwrap_or_default();
+        };
+    }
+    match field.as_int_primitive() {
+        None => quote! {
+            let #name = summon_from_bytes::<#tpe>(bytes, strict)?;
+            bytes = bytes.get(#name.size()..).ok_or(Error::PayloadTooSmall)?;
+        },
+        Some((size, tpe, e)) => {
+            let func = format_ident!("from_{}_bytes", e);
+            let tpei = format_ident!("{}", tpe);
+
+            /* checked split, malformed input must not panic */
+            quote! {
+                let Some((chunk, rest)) = bytes.split_first_chunk::<#size>() else {
+                    return Err(Error::PayloadTooSmall);
+                };
+                let #name = #tpei::#func(*chunk);
+                bytes = rest;
+            }
+        }
+    }
 }
 
 
@@ -212,38 +431,116 @@ pub fn derive_serializable(tokens: TokenStream) -> TokenStream
     let s = StructPrototype::from_tok_stream(tokens).expect("can only apply to struct");
     let write = s.fields.iter().map(|field| {
         let name = format_ident!("{}", &field.name);
-        match field.as_int_primitive() {
-            None => quote! {
-                    self.#name.write_to_slice(bytes, strict)?;
-                    bytes = &mut bytes[self.#name.size()..];
-                },
-            Some((size, _t, endian)) => {
-                    let func = format_ident!("to_{}_bytes", endian);
-                    quote! {
-                        bytes[..#size].copy_from_slice(&self.#name.#func());
-                        bytes = &mut bytes[#size..];
-                    }
-            }
+        let offset = offset_ident(&field.name);
+        let write = field_write(field, &name);
+        quote! {
+            let #offset = total - bytes.len();
+            #write
         }
     });
+
+    /* the checksum fields are written as is, then overwritten here */
+    let patch = s.checksums().into_iter().map(|(checksum, start, end)| quote! {
+        slice[#checksum] = slice[#start..#end].iter().fold(0u8, |acc, b| acc.wrapping_add(*b)).wrapping_neg();
+    });
+
+    let header = s.impl_header(quote! { BytesSerializable });
     let struct_name = format_ident!("{}", s.name);
 
-    let ret = (quote! {
-        impl BytesSerializable for #struct_name {
+    (quote! {
+        #header {
             fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
             {
                 if slice.len() < self.size() {
                     return Err(Error::OutBufferTooSmall);
                 }
 
-                let mut bytes = slice;
+                let total = slice.len();
+                let mut bytes = &mut *slice;
                 #(#write ;)*
 
+                /* catch layout drift between the fields written and size() */
+                debug_assert_eq!(total - bytes.len(), self.size(),
+                    "bytes written by {} disagree with its size()", stringify!(#struct_name));
+
+                #(#patch)*
+
                 Ok(())
             }
         }
-    }).into();
+    }).into()
+}
 
-    ret
+/* write one field to the front of `bytes` and advance past it */
+fn field_write(field: &Field, name: &syn::Ident) -> proc_macro2::TokenStream {
+    if let Some(value) = field.reserved() {
+        return quote! {
+            bytes[0] = #value;
+            bytes = &mut bytes[1..];
+        };
+    }
+    if field.is_len_prefixed() {
+        return quote! {
+            let len = self.#name.len();
+            bytes[0] = u8::try_from(len).map_err(|_| Error::PayloadTooLarge)?;
+            bytes[1..1 + len].copy_from_slice(self.#name);
+            bytes = &mut bytes[1 + len..];
+        };
+    }
+    if field.is_trailing() {
+        return quote! {
+            if let Some(byte) = self.#name {
+                bytes[0] = byte;
+                bytes = &mut bytes[1..];
+            }
+        };
+    }
+    match field.as_int_primitive() {
+        None => quote! {
+                self.#name.write_to_slice(bytes, strict)?;
+                bytes = &mut bytes[self.#name.size()..];
+            },
+        Some((size, _t, endian)) => {
+                let func = format_ident!("to_{}_bytes", endian);
+                quote! {
+                    bytes[..#size].copy_from_slice(&self.#name.#func());
+                    bytes = &mut bytes[#size..];
+                }
+        }
+    }
 }
 
+/* 
+ * Zero every field, integers with an endian attribute included, so requests
+ * can be written as `Request { field: 1, ..Default::default() }`.
+ */
+#[proc_macro_derive(BytesDefault, attributes(bytes_serialize))]
+pub fn derive_bytes_default(tokens: TokenStream) -> TokenStream
+{
+    let s = StructPrototype::from_tok_stream(tokens).expect("can only apply to struct");
+    let defaults = s.fields.iter().map(|field| {
+        let name = format_ident!("{}", &field.name);
+        if let Some(value) = field.reserved() {
+            return quote! { #name: #value };
+        }
+        match &field.tpe {
+            /* arrays only implement Default up to 32 elements */
+            syn::Type::Array(arr) => {
+                let len = &arr.len;
+                quote! { #name: [::core::default::Default::default(); #len] }
+            },
+            _ => quote! { #name: ::core::default::Default::default() }
+        }
+    });
+    let header = s.impl_header(quote! { ::core::default::Default });
+
+    (quote! {
+        #header {
+            fn default() -> Self {
+                Self {
+                    #(#defaults ,)*
+                }
+            }
+        }
+    }).into()
+}
@@ -0,0 +1,74 @@
+/*
+ * Round trips of IpmiLanClient::call against a loopback responder, counting
+ * the allocations of the whole process once the client buffer is allocated.
+ *
+ *   cargo bench -p mini_ipmi --features client --bench client_call
+ */
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use mini_ipmi::ipmi::asf::AsfMessage;
+use mini_ipmi::ipmi::client::IpmiLanClient;
+use mini_ipmi::ipmi::rmcp::RmcpMessage;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const WARMUP: usize = 16;
+const CALLS:  usize = 10_000;
+
+fn main() {
+    let bmc = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut client = IpmiLanClient::connect(bmc.local_addr().unwrap()).unwrap();
+
+    let responder = std::thread::spawn(move || {
+        let mut pong = [0u8; 64];
+        let pong_len = RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00))
+            .write_into(&mut pong, true).unwrap();
+
+        let mut buf = [0u8; 64];
+        for _ in 0..WARMUP + CALLS {
+            let (_, from) = bmc.recv_from(&mut buf).unwrap();
+            bmc.send_to(&pong[..pong_len], from).unwrap();
+        }
+    });
+
+    let ping = RmcpMessage::from_asf(AsfMessage::ping());
+    let timeout = Duration::from_secs(5);
+
+    for _ in 0..WARMUP {
+        client.call(&ping, timeout).unwrap();
+    }
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..CALLS {
+        let pong = client.call(&ping, timeout).unwrap();
+        std::hint::black_box(&pong);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    responder.join().unwrap();
+
+    println!("{} calls in {:?}, {:?} per call, {} allocations",
+        CALLS, elapsed, elapsed / CALLS as u32, allocations);
+    assert_eq!(allocations, 0, "IpmiLanClient::call allocated after warmup");
+}
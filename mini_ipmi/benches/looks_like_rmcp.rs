@@ -0,0 +1,58 @@
+/*
+ * Cost of dropping stray UDP 623 traffic with RmcpMessage::looks_like_rmcp
+ * against a full RmcpMessage::from_bytes of the same datagrams.
+ *
+ *   cargo bench -p mini_ipmi --bench looks_like_rmcp
+ */
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use mini_ipmi::ipmi::BytesDeserializable;
+use mini_ipmi::ipmi::rmcp::RmcpMessage;
+
+const ROUNDS: usize = 1_000;
+
+fn datagrams() -> Vec<Vec<u8>> {
+    let auth_cap_req = [
+        0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,
+        0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31
+    ];
+    let ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00];
+
+    /* mostly scanner noise, with a few real frames */
+    let mut state = 0x9e3779b97f4a7c15u64;
+    (0..1024).map(|i| match i % 16 {
+        0 => auth_cap_req.to_vec(),
+        8 => ping.to_vec(),
+        _ => (0..64).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        }).collect()
+    }).collect()
+}
+
+fn run(name: &str, datagrams: &[Vec<u8>], f: impl Fn(&[u8]) -> bool) -> Duration {
+    let mut accepted = 0;
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for datagram in datagrams {
+            accepted += usize::from(f(black_box(datagram)));
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let calls = (ROUNDS * datagrams.len()) as u32;
+    println!("{:16} {:?} per datagram, {} accepted", name, elapsed / calls, accepted / ROUNDS);
+    elapsed
+}
+
+fn main() {
+    let datagrams = datagrams();
+
+    let quick = run("looks_like_rmcp", &datagrams, RmcpMessage::looks_like_rmcp);
+    let full  = run("from_bytes", &datagrams, |bytes| RmcpMessage::from_bytes(bytes, true).is_ok());
+
+    println!("looks_like_rmcp is {:.1}x cheaper", full.as_secs_f64() / quick.as_secs_f64());
+}
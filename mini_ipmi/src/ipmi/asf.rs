@@ -1,8 +1,12 @@
 
-use crate::{take_be_u32, take_u8};
+use crate::{take_be_u32, take_u8, take_remain};
 use crate::ipmi::*;
+use crate::ipmi::util::parse_warn;
 
 pub const ASF_IANA: u32 = 4542;
+
+/* IANA, OEM defined, entities, interactions and 6 reserved bytes */
+pub const ASF_PONG_DATA_LEN: u8 = 16;
 pub const ENTITY_IPMI: u8 = 0b10000000;
 pub const ENTITY_ASF1: u8 = 0b00000001;
 
@@ -13,23 +17,32 @@ pub const ASF_MSG_TYPE_PING: u8 = 0x80;
 pub const ASF_MSG_TYPE_PONG: u8 = 0x40;
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsfMessage<'a> {
     pub iana: u32,
     pub msg_type: u8,
     pub msg_tag:  u8,
     pub data_len:  u8,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub data: AsfData<'a>
 
 }
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AsfData<'a> {
-    Other(&'a [u8]),
+    Other(#[cfg_attr(feature = "serde", serde(with = "serde_bytes"))] &'a [u8]),
     Ping,
     Pong { iana: u32
          , oem_defined: u32
          , entities: u8
          , interactions: u8
+         /* 
+          * OEM data some implementations append past the 16 bytes pong, the
+          * reserved bytes in between are written as zeros
+          */
+         , #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+           oem_data: &'a [u8]
          }
 }
 
@@ -37,7 +50,7 @@ impl BytesSerializationSized for AsfData<'_> {
     fn size(&self) -> usize {
         match self {
             AsfData::Ping => 0,
-            AsfData::Pong { .. }  => 10,
+            AsfData::Pong { oem_data, .. } => usize::from(ASF_PONG_DATA_LEN) + oem_data.len(),
             AsfData::Other(bytes) => bytes.len()
         }
     }
@@ -53,11 +66,13 @@ impl BytesSerializable for AsfData<'_>
 
         match self {
             AsfData::Ping => Ok(()),
-            AsfData::Pong { iana, oem_defined, entities, interactions } => {
+            AsfData::Pong { iana, oem_defined, entities, interactions, oem_data } => {
                 slice[0..4].copy_from_slice(&iana.to_be_bytes());
                 slice[4..8].copy_from_slice(&oem_defined.to_be_bytes());
                 slice[8] = *entities;
                 slice[9] = *interactions;
+                slice[10..16].fill(0);
+                slice[16..][..oem_data.len()].copy_from_slice(oem_data);
                 Ok(())
             },
             AsfData::Other(bytes) => {
@@ -82,12 +97,12 @@ impl BytesSerializable for AsfMessage<'_>
     {
         if bytes.len() < self.size() {
             Err(Error::OutBufferTooSmall)
-        } else if strict && self.data.size() != self.data_len as usize {
+        } else if strict && (self.data.size() > IPMI_MAX_PAYLOAD || self.data.size() != self.data_len as usize) {
             Err(Error::InvalidConfiguration)
         } else { 
             let valid_config = !strict || match self.msg_type {
                 ASF_MSG_TYPE_PING => self.data_len == 0,
-                ASF_MSG_TYPE_PONG => self.data_len == 10,
+                ASF_MSG_TYPE_PONG => self.data_len == ASF_PONG_DATA_LEN,
                 _ => true
             };
 
@@ -97,8 +112,9 @@ impl BytesSerializable for AsfMessage<'_>
                 bytes[..4].copy_from_slice(&self.iana.to_be_bytes());
                 bytes[4] = self.msg_type;
                 bytes[5] = self.msg_tag;
-                bytes[6] = self.data.size() as u8;
-                self.data.write_to_slice(&mut bytes[7..], strict)?;
+                bytes[6] = 0x00;
+                bytes[7] = self.data.size() as u8;
+                self.data.write_to_slice(&mut bytes[8..], strict)?;
                 Ok(())
             }
         }
@@ -125,8 +141,8 @@ impl<'a> AsfMessage<'a>
             iana:     ASF_IANA,
             msg_type: ASF_MSG_TYPE_PONG,
             msg_tag:  0,
-            data_len: 10,
-            data:     AsfData::Pong {iana, oem_defined, entities, interactions}
+            data_len: ASF_PONG_DATA_LEN,
+            data:     AsfData::Pong {iana, oem_defined, entities, interactions, oem_data: &[]}
         }
     }
 
@@ -135,12 +151,13 @@ impl<'a> AsfMessage<'a>
     }
 
     pub fn is_pong(&self) -> bool {
-        self.data_len == 16 && self.msg_type == ASF_MSG_TYPE_PONG
+        self.data_len == ASF_PONG_DATA_LEN && self.msg_type == ASF_MSG_TYPE_PONG
     }
 }
 
 impl<'a> BytesDeserializable<'a> for AsfMessage<'a>
 {
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
     fn from_bytes(bytes: &'a [u8], strict: bool) -> Result<AsfMessage<'a>, Error>
     {
         /* 
@@ -153,43 +170,95 @@ impl<'a> BytesDeserializable<'a> for AsfMessage<'a>
          * | Data       (var) |
          * +------------------+
          */
-        /* ASF message should have at least 8 bytes, data have most 255 bytes */
+        /* ASF message should have at least 8 bytes, data have most IPMI_MAX_PAYLOAD bytes */
         if bytes.len() < 8 {
             Err(Error::PayloadTooSmall)
-        } else if strict && usize::from(bytes[7]) + 8 != bytes.len() {
-            Err(Error::ExpectedSizeMismatch)
         } else {
-            let iana = u32::from_be_bytes(bytes[0..4].try_into().unwrap());
-            let msg_type = bytes[4];
-            let msg_tag  = bytes[5];
-            let data_len  = bytes[7];
-            let mut idx = 8;
+            let mut idx  = 0;
+            let iana     = take_be_u32!(bytes, idx);
+            let msg_type = take_u8!(bytes, idx);
+            let msg_tag  = take_u8!(bytes, idx);
+            let _        = take_u8!(bytes, idx);
+            let data_len = take_u8!(bytes, idx);
+
+            if strict && usize::from(data_len) + 8 != bytes.len() {
+                parse_warn!("ASF data length {=u8} disagrees with {=usize} bytes payload",
+                    data_len, bytes.len());
+                return Err(Error::ExpectedSizeMismatch {
+                    expected: usize::from(data_len) + 8, actual: bytes.len() });
+            }
 
             let data = match msg_type {
                 ASF_MSG_TYPE_PING =>
                     if strict && data_len != 0 { 
-                        Err(Error::ExpectedSizeMismatch)
+                        parse_warn!("ASF ping carries {=u8} data bytes", data_len);
+                        Err(Error::ExpectedSizeMismatch {
+                            expected: 0, actual: usize::from(data_len) })
                     } else { 
                         Ok(AsfData::Ping)
                     },
                 ASF_MSG_TYPE_PONG => {
-                    if bytes.len() < 18 {
+                    /* a legacy 10 bytes pong only decodes when not strict */
+                    let min_len = if strict { 8 + usize::from(ASF_PONG_DATA_LEN) } else { 18 };
+                    if bytes.len() < min_len {
+                        parse_warn!("ASF pong of {=usize} bytes is too short", bytes.len());
                         Err(Error::PayloadTooSmall)
-                    } else if strict && data_len > 10 {
+                    } else if strict && data_len > ASF_PONG_DATA_LEN {
+                        parse_warn!("ASF pong carries {=u8} data bytes", data_len);
                         Err(Error::PayloadTooLarge)
                     } else {
                         let iana         = take_be_u32!(bytes, idx);
                         let oem_defined  = take_be_u32!(bytes, idx);
                         let entities     = take_u8!(bytes, idx);
-                        let interactions = bytes[idx];
+                        let interactions = take_u8!(bytes, idx);
+                        /* skip the reserved bytes, OEM data is only reachable when not strict */
+                        let end = bytes.len().min(8 + usize::from(data_len));
+                        let oem_data = bytes.get(idx + 6..end).unwrap_or_default();
                         Ok(AsfData::Pong {
-                            iana, oem_defined, entities, interactions })
+                            iana, oem_defined, entities, interactions, oem_data })
                     }
                 },
-                _ => Ok(AsfData::Other(&bytes[8..]))
+                _ => Ok(AsfData::Other(take_remain!(bytes, idx)))
             };
 
             data.map(|data| AsfMessage { iana, msg_type, msg_tag, data_len, data })
         }
     }
 }
+
+#[cfg(feature = "alloc")]
+impl AsfMessage<'_> {
+    pub fn to_vec(&self, strict: bool) -> Result<alloc::vec::Vec<u8>, Error> {
+        crate::ipmi::to_vec(self, strict)
+    }
+}
+
+/* pings, pongs and other message types that serialize in strict mode */
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AsfMessage<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let msg_tag = u.arbitrary()?;
+
+        let msg = match u.int_in_range(0..=2)? {
+            0 => AsfMessage::ping(),
+            1 => AsfMessage::pong(u.arbitrary()?, u.arbitrary()?, u.arbitrary()?, u.arbitrary()?),
+            _ => {
+                let mut msg_type = u.arbitrary()?;
+                while msg_type == ASF_MSG_TYPE_PING || msg_type == ASF_MSG_TYPE_PONG {
+                    msg_type = u.arbitrary()?;
+                }
+                let len  = u.int_in_range(0..=IPMI_MAX_PAYLOAD)?;
+                let data = u.bytes(len)?;
+                AsfMessage {
+                    iana: u.arbitrary()?,
+                    msg_type,
+                    msg_tag,
+                    data_len: data.len() as u8,
+                    data: AsfData::Other(data)
+                }
+            }
+        };
+
+        Ok(AsfMessage { msg_tag, ..msg })
+    }
+}
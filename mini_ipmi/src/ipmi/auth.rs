@@ -0,0 +1,190 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::ipmi::Error;
+
+/* 
+ * HMAC algorithms of the RAKP exchange and the integrity of RMCP+ sessions,
+ * the RAKP algorithm of a cipher suite picks the integrity algorithm too.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RakpHmac {
+    Sha1,
+    Sha256
+}
+
+impl RakpHmac {
+    /* length of a full HMAC, also of the SIK and of K1/K2 */
+    pub fn mac_len(&self) -> usize {
+        match self {
+            RakpHmac::Sha1   => 20,
+            RakpHmac::Sha256 => 32
+        }
+    }
+
+    /* length of the auth code of HMAC-SHA1-96 / HMAC-SHA256-128 */
+    pub fn integrity_len(&self) -> usize {
+        match self {
+            RakpHmac::Sha1   => 12,
+            RakpHmac::Sha256 => 16
+        }
+    }
+
+    /* HMAC of the concatenation of `parts`, written to `out`, return its length */
+    pub fn mac(&self, key: &[u8], parts: &[&[u8]], out: &mut [u8]) -> Result<usize, Error> {
+        let len = self.mac_len();
+        let out = out.get_mut(..len).ok_or(Error::OutBufferTooSmall)?;
+
+        match self {
+            RakpHmac::Sha1 => {
+                let mut mac = <Hmac<Sha1>>::new_from_slice(key)
+                    .map_err(|_| Error::InvalidConfiguration)?;
+                parts.iter().for_each(|part| mac.update(part));
+                out.copy_from_slice(&mac.finalize().into_bytes());
+            },
+            RakpHmac::Sha256 => {
+                let mut mac = <Hmac<Sha256>>::new_from_slice(key)
+                    .map_err(|_| Error::InvalidConfiguration)?;
+                parts.iter().for_each(|part| mac.update(part));
+                out.copy_from_slice(&mac.finalize().into_bytes());
+            }
+        }
+
+        Ok(len)
+    }
+}
+
+/* 
+ * Compare auth codes in time independent of where they differ, lengths are
+ * not secret and differing lengths return early
+ */
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+pub fn rakp_hmac_sha1(key: &[u8], data: &[u8], out: &mut [u8; 20]) {
+    /* cannot fail, HMAC takes keys of any length and `out` fits */
+    let _ = RakpHmac::Sha1.mac(key, &[data], out);
+}
+
+pub fn rakp_hmac_sha256(key: &[u8], data: &[u8], out: &mut [u8; 32]) {
+    let _ = RakpHmac::Sha256.mac(key, &[data], out);
+}
+
+/* values exchanged in RAKP 1 and 2 that the auth codes and the SIK cover */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RakpParams<'a> {
+    pub console_session_id: u32,
+    pub managed_session_id: u32,
+    pub console_random: [u8; 16],
+    pub managed_random: [u8; 16],
+    pub managed_guid: [u8; 16],
+    /* requested maximum privilege level, with the name-only lookup bit */
+    pub role: u8,
+    /* at most 16 bytes */
+    pub username: &'a [u8]
+}
+
+impl RakpParams<'_> {
+    fn username_len(&self) -> Result<[u8; 1], Error> {
+        match u8::try_from(self.username.len()) {
+            Ok(len) if len <= 16 => Ok([len]),
+            _ => Err(Error::PayloadTooLarge)
+        }
+    }
+
+    /* 
+     * Key exchange auth code of RAKP 2, 
+     * HMAC_Kuid(SIDm | SIDc | Rm | Rc | GUIDc | ROLEm | ULENGTHm | UNAMEm)
+     */
+    pub fn rakp2_auth_code(&self, alg: RakpHmac, kuid: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+        alg.mac(kuid, &[
+            &self.console_session_id.to_le_bytes(),
+            &self.managed_session_id.to_le_bytes(),
+            &self.console_random,
+            &self.managed_random,
+            &self.managed_guid,
+            &[self.role],
+            &self.username_len()?,
+            self.username
+        ], out)
+    }
+
+    /* 
+     * Key exchange auth code of RAKP 3, 
+     * HMAC_Kuid(Rc | SIDm | ROLEm | ULENGTHm | UNAMEm)
+     */
+    pub fn rakp3_auth_code(&self, alg: RakpHmac, kuid: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+        alg.mac(kuid, &[
+            &self.managed_random,
+            &self.console_session_id.to_le_bytes(),
+            &[self.role],
+            &self.username_len()?,
+            self.username
+        ], out)
+    }
+
+    /* 
+     * Session integrity key, HMAC_Kg(Rm | Rc | ROLEm | ULENGTHm | UNAMEm),
+     * Kuid is used in place of Kg when no Kg is configured
+     */
+    pub fn session_integrity_key(&self, alg: RakpHmac, kg: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+        alg.mac(kg, &[
+            &self.console_random,
+            &self.managed_random,
+            &[self.role],
+            &self.username_len()?,
+            self.username
+        ], out)
+    }
+
+    /* 
+     * Integrity check value of RAKP 4, HMAC_SIK(Rm | SIDc | GUIDc), truncated
+     * to the integrity length of the algorithm
+     */
+    pub fn rakp4_integrity_check(&self, alg: RakpHmac, sik: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+        let mut full = [0u8; 32];
+        alg.mac(sik, &[
+            &self.console_random,
+            &self.managed_session_id.to_le_bytes(),
+            &self.managed_guid
+        ], &mut full)?;
+
+        truncate_into(&full, alg.integrity_len(), out)
+    }
+}
+
+fn truncate_into(full: &[u8], len: usize, out: &mut [u8]) -> Result<usize, Error> {
+    let out = out.get_mut(..len).ok_or(Error::OutBufferTooSmall)?;
+    out.copy_from_slice(full.get(..len).ok_or(Error::InvalidConfiguration)?);
+    Ok(len)
+}
+
+/* 
+ * Key n derived from the SIK, HMAC_SIK(n repeated for the length of the
+ * HMAC). K1 keys the integrity algorithm, K2 the confidentiality one.
+ */
+pub fn derive_key(alg: RakpHmac, sik: &[u8], n: u8, out: &mut [u8]) -> Result<usize, Error> {
+    let constant = [n; 32];
+    alg.mac(sik, &[&constant[..alg.mac_len()]], out)
+}
+
+/* auth code of a RMCP+ packet, HMAC-SHA1-96 or HMAC-SHA256-128 keyed with K1 */
+pub fn integrity_auth_code(alg: RakpHmac, k1: &[u8], data: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let mut full = [0u8; 32];
+    alg.mac(k1, &[data], &mut full)?;
+    truncate_into(&full, alg.integrity_len(), out)
+}
+
+/* whether `auth_code` is the auth code of `data` */
+pub fn verify_integrity_auth_code(alg: RakpHmac, k1: &[u8], data: &[u8], auth_code: &[u8]) -> Result<bool, Error> {
+    let mut expected = [0u8; 16];
+    let len = integrity_auth_code(alg, k1, data, &mut expected)?;
+    Ok(constant_time_eq(&expected[..len], auth_code))
+}
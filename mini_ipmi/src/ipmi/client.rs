@@ -0,0 +1,104 @@
+use std::io;
+use std::vec;
+use std::vec::Vec;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use crate::ipmi::owned::RmcpMessageOwned;
+use crate::ipmi::rmcp::RmcpMessage;
+use crate::ipmi::{BytesDeserializable, CallError};
+
+/* the RMCP port of a BMC */
+pub const IPMI_LAN_PORT: u16 = 623;
+
+/* large enough for any IPMI 1.5 or RMCP+ datagram */
+const RECV_BUF_LEN: usize = 1024;
+
+/*
+ * A UDP socket connected to a BMC, exchanging RMCP messages. Every call
+ * maps socket errors to `CallError::Transport`; the socket is public for
+ * anything not covered here, e.g. non-blocking I/O.
+ */
+#[derive(Debug)]
+pub struct IpmiLanClient {
+    pub socket: UdpSocket,
+    /* encodes the request and holds the response of `call` */
+    buf: Vec<u8>
+}
+
+impl IpmiLanClient {
+    /* connect to the RMCP port of `bmc` */
+    pub fn new(bmc: IpAddr) -> io::Result<IpmiLanClient> {
+        IpmiLanClient::connect((bmc, IPMI_LAN_PORT))
+    }
+
+    /* connect to a BMC listening on some other address, e.g. behind NAT */
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<IpmiLanClient> {
+        let addr = addr.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to"))?;
+
+        let local = match addr {
+            SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0))
+        };
+
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(IpmiLanClient::from_socket(socket))
+    }
+
+    pub fn from_socket(socket: UdpSocket) -> IpmiLanClient {
+        IpmiLanClient { socket, buf: Vec::new() }
+    }
+
+    /* 
+     * size the buffer reused by `call` to the largest message exchanged,
+     * otherwise it is allocated on the first call with room for any
+     * IPMI 1.5 or RMCP+ datagram
+     */
+    pub fn with_max_message_len(mut self, len: usize) -> IpmiLanClient {
+        self.buf = vec![0; len];
+        self
+    }
+
+    /* serialize `msg` strictly and send it as one datagram */
+    pub fn send(&self, msg: &RmcpMessage) -> Result<usize, CallError<io::Error>> {
+        let mut buf = [0u8; RECV_BUF_LEN];
+        let len = msg.write_into(&mut buf, true).map_err(CallError::Parse)?;
+        self.socket.send(&buf[..len]).map_err(CallError::Transport)
+    }
+
+    /*
+     * send `msg` and wait up to `timeout` for the response, which borrows
+     * the buffer of the client until the next call, so nothing is allocated
+     * past the first call
+     */
+    pub fn call(&mut self, msg: &RmcpMessage, timeout: Duration) -> Result<RmcpMessage<'_>, CallError<io::Error>> {
+        if self.buf.is_empty() {
+            self.buf = vec![0; RECV_BUF_LEN];
+        }
+
+        let len = msg.write_into(&mut self.buf, true).map_err(CallError::Parse)?;
+        self.socket.send(&self.buf[..len]).map_err(CallError::Transport)?;
+
+        self.socket.set_read_timeout(Some(timeout)).map_err(CallError::Transport)?;
+        let len = self.socket.recv(&mut self.buf).map_err(CallError::Transport)?;
+
+        RmcpMessage::from_bytes(&self.buf[..len], true).map_err(CallError::Parse)
+    }
+
+    /*
+     * wait up to `timeout` for a datagram and parse it, an expired timeout
+     * surfaces as a `WouldBlock` or `TimedOut` transport error depending on
+     * the platform
+     */
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<RmcpMessageOwned, CallError<io::Error>> {
+        self.socket.set_read_timeout(Some(timeout)).map_err(CallError::Transport)?;
+
+        let mut buf = [0u8; RECV_BUF_LEN];
+        let len = self.socket.recv(&mut buf).map_err(CallError::Transport)?;
+
+        let msg = RmcpMessage::from_bytes(&buf[..len], true).map_err(CallError::Parse)?;
+        Ok(msg.to_owned())
+    }
+}
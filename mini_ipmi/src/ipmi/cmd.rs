@@ -5,67 +5,736 @@ use macros::*;
 use crate::ipmi::summon_from_bytes;
 use crate::ipmi::{BytesDeserializable, BytesSerializationSized, BytesSerializable};
 use crate::ipmi::Error;
+use crate::ipmi::ipmi::{IpmiMessage, PrivLevel};
+use crate::ipmi::rmcp::{RmcpContent, RmcpFrames, RmcpMessage};
+use crate::ipmi::sol::*;
+use crate::ipmi::time::IpmiTimestamp;
 
-pub trait IpmiCommand: core::marker::Sized {
-    fn from_data(data: &crate::ipmi::ipmi::IpmiData) -> Option<Self>;
-    fn from_message(msg: &crate::ipmi::ipmi::IpmiMessage) -> Option<Self>;
+pub trait IpmiCommand<'a>: core::marker::Sized {
+    fn from_data(data: &crate::ipmi::ipmi::IpmiData<'a>) -> Option<Self>;
+    fn from_message(msg: &crate::ipmi::ipmi::IpmiMessage<'a>) -> Option<Self>;
 }
 
 macro_rules! ipmi_cmd {
+    (@impl $netfn:expr, $cmd:expr, $req:ty, $res:ty) => {
+        fn from_data(data: &crate::ipmi::ipmi::IpmiData<'a>) -> Option<Self> {
+            match data {
+                crate::ipmi::ipmi::IpmiData::Request(dat) => {
+                    <$req>::from_bytes(dat, true).ok()
+                        .map(|req| Self::Request(req))
+                },
+                crate::ipmi::ipmi::IpmiData::Response(code, dat) => {
+                    <$res>::from_bytes(dat, true).ok()
+                        .map(|res| Self::Response(*code, res))
+                }
+            }
+        }
+
+        fn from_message(msg: &crate::ipmi::ipmi::IpmiMessage<'a>) -> Option<Self>
+        {
+            let netfn = if msg.netfn % 2 == 0 { 
+                msg.netfn
+            } else {
+                msg.netfn - 1
+            };
+
+            if msg.cmd != $cmd || netfn != $netfn { return None; }
+
+            Self::from_data(&msg.data)
+        }
+    };
+    /* for commands which request or response borrow from the message */
+    ($netfn:expr, $cmd:expr, $name:ident<'a>, $req:ty, $res:ty) => {
+        #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+        pub enum $name<'a> {
+            Request($req),
+            Response(u8, $res)
+        }
+
+        impl<'a> IpmiCommand<'a> for $name<'a> {
+            ipmi_cmd!(@impl $netfn, $cmd, $req, $res);
+        }
+    };
     ($netfn:expr, $cmd:expr, $name:ident, $req:ty, $res:ty) => {
-        #[derive(Debug, Eq, PartialEq)]
+        #[derive(Debug, Eq, PartialEq, Clone, Hash)]
         pub enum $name {
             Request($req),
             Response(u8, $res)
         }
 
-        impl IpmiCommand for $name {
-            fn from_data(data: &crate::ipmi::ipmi::IpmiData) -> Option<Self> {
-                match data {
-                    crate::ipmi::ipmi::IpmiData::Request(dat) => {
-                        <$req>::from_bytes(dat, true).ok()
-                            .map(|req| Self::Request(req))
-                    },
-                    crate::ipmi::ipmi::IpmiData::Response(code, dat) => {
-                        <$res>::from_bytes(dat, true).ok()
-                            .map(|res| Self::Response(*code, res))
-                    }
+        impl<'a> IpmiCommand<'a> for $name {
+            ipmi_cmd!(@impl $netfn, $cmd, $req, $res);
+        }
+    };
+    ($netfn:expr, $cmd:expr, $name:ident) => {
+        paste! {
+            ipmi_cmd!($netfn, $cmd, $name, [<$name Request>], [<$name Response>]);
+        }
+    };
+}
+
+/* 
+ * Declares every command with `ipmi_cmd!` and collects them in `KnownCommand`
+ * so an incoming message can be identified with `decode_command`.
+ */
+macro_rules! ipmi_cmds {
+    ($( ($netfn:expr, $cmd:expr, $name:ident $(<$lt:lifetime>)? $(, $req:ty, $res:ty)?) ),* $(,)?) => {
+        $( ipmi_cmd!($netfn, $cmd, $name $(<$lt>)? $(, $req, $res)?); )*
+
+        #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+        pub enum KnownCommand<'a> {
+            $( $name($name $(<$lt>)?), )*
+        }
+
+        impl KnownCommand<'_> {
+            /* the (netfn, cmd) pair, usable as a key regardless of the payload */
+            pub fn netfn_cmd(&self) -> (u8, u8) {
+                match self {
+                    $( KnownCommand::$name(_) => ($netfn, $cmd), )*
                 }
             }
+        }
 
-            fn from_message(msg: &crate::ipmi::ipmi::IpmiMessage) -> Option<Self>
-            {
-                let netfn = if msg.netfn % 2 == 0 { 
-                    msg.netfn
-                } else {
-                    msg.netfn - 1
-                };
+        /* None if the netfn/cmd pair is unknown or the payload is malformed */
+        pub fn decode_command<'a>(msg: &IpmiMessage<'a>) -> Option<KnownCommand<'a>> {
+            let netfn = msg.netfn & !1;
 
-                if msg.cmd != $cmd || netfn != $netfn { return None; }
+            $(
+                if netfn == $netfn && msg.cmd == $cmd {
+                    return $name::from_message(msg).map(KnownCommand::$name);
+                }
+            )*
 
-                Self::from_data(&msg.data)
-            }
+            None
         }
     };
-    ($netfn:expr, $cmd:expr, $name:ident) => {
-        paste! {
-            ipmi_cmd!($netfn, $cmd, $name, [<$name Request>], [<$name Response>]);
+}
+
+/* a command found in a datagram, or the frame if it is not a known command */
+#[derive(Debug, Eq, PartialEq)]
+pub enum AnyIpmiCommand<'a> {
+    Known(KnownCommand<'a>),
+    Raw(RmcpMessage<'a>)
+}
+
+/* split `buf` into RMCP frames and decode the command carried by each */
+pub fn decode_all_commands(buf: &[u8], strict: bool) 
+    -> impl Iterator<Item = Result<AnyIpmiCommand<'_>, Error>>
+{
+    RmcpFrames::new(buf, strict).map(|frame| frame.map(|msg| {
+        let known = match &msg.data {
+            RmcpContent::Ipmi15(packet) => decode_command(&packet.data),
+            _ => None
+        };
+
+        match known {
+            Some(cmd) => AnyIpmiCommand::Known(cmd),
+            None      => AnyIpmiCommand::Raw(msg)
         }
-    };
+    }))
+}
+
+ipmi_cmds! {
+    (0x00, 0x00, GetChassisCapabilities),
+    (0x00, 0x08, SetSystemBootOptions<'a>, SetSystemBootOptionsRequest<'a>, SetSystemBootOptionsResponse),
+    (0x00, 0x09, GetSystemBootOptions<'a>, GetSystemBootOptionsRequest, GetSystemBootOptionsResponse<'a>),
+    (0x00, 0x0f, GetPohCounter),
+
+    (0x06, 0x01, GetDeviceId<'a>, GetDeviceIdRequest, GetDeviceIdResponse<'a>),
+    (0x06, 0x02, ColdReset),
+    (0x06, 0x03, WarmReset),
+    (0x06, 0x04, GetSelfTestResults),
+    (0x06, 0x06, SetAcpiPowerState),
+    (0x06, 0x07, GetAcpiPowerState),
+    (0x06, 0x22, ResetWatchdogTimer),
+    (0x06, 0x24, SetWatchdogTimer),
+    (0x06, 0x25, GetWatchdogTimer),
+
+    (0x06, 0x31, GetMessageFlags),
+    (0x06, 0x32, ClearMessageFlags),
+    (0x06, 0x33, GetMessage<'a>, GetMessageRequest, GetMessageResponse<'a>),
+    (0x06, 0x34, SendMessage<'a>, SendMessageRequest<'a>, SendMessageResponse<'a>),
+
+    (0x06, 0x38, GetChannelAuthCap),
+    (0x06, 0x39, GetSessionChallenge),
+    (0x06, 0x3a, ActivateSession),
+    (0x06, 0x3b, SetSessionPrivLevel),
+    (0x06, 0x3c, CloseSession),
+
+    (0x06, 0x40, SetChannelAccess),
+    (0x06, 0x41, GetChannelAccess),
+    (0x06, 0x42, GetChannelInfo),
+
+    (0x06, 0x44, GetUserAccess),
+    (0x06, 0x45, SetUserName),
+    (0x06, 0x46, GetUserName),
+
+    (0x06, 0x48, ActivateSolPayload),
+    (0x06, 0x52, MasterWriteRead<'a>, MasterWriteReadRequest<'a>, MasterWriteReadResponse<'a>),
+
+    (0x0c, 0x01, SetLanConfigParam<'a>, SetLanConfigParamRequest<'a>, SetLanConfigParamResponse),
+    (0x0c, 0x02, GetLanConfigParam<'a>, GetLanConfigParamRequest, GetLanConfigParamResponse<'a>),
+    (0x0c, 0x21, SetSolConfigParam<'a>, SetSolConfigParamRequest<'a>, SetSolConfigParamResponse),
+
+    (0x0a, 0x10, GetFruInventoryAreaInfo),
+    (0x0a, 0x11, ReadFruData<'a>, ReadFruDataRequest, ReadFruDataResponse<'a>),
+
+    (0x0a, 0x20, GetSdrRepositoryInfo),
+    (0x0a, 0x22, ReserveSdrRepository),
+    (0x0a, 0x23, GetSdr<'a>, GetSdrRequest, GetSdrResponse<'a>),
+
+    (0x0a, 0x40, GetSelInfo),
+    (0x0a, 0x42, ReserveSel),
+    (0x0a, 0x43, GetSelEntry),
+    (0x0a, 0x44, AddSelEntry),
+    (0x0a, 0x47, ClearSel),
+    (0x0a, 0x48, GetSelTime),
+    (0x0a, 0x49, SetSelTime),
 }
 
-ipmi_cmd!(0x06, 0x38, GetChannelAuthCap);
-ipmi_cmd!(0x06, 0x39, GetSessionChallenge);
-ipmi_cmd!(0x06, 0x3a, ActivateSession);
-ipmi_cmd!(0x06, 0x3b, SetSessionPrivLevel);
+pub const BOOT_PARAM_SET_IN_PROGRESS:        u8 = 0;
+pub const BOOT_PARAM_SERVICE_PARTITION:      u8 = 1;
+pub const BOOT_PARAM_SERVICE_PARTITION_SCAN: u8 = 2;
+pub const BOOT_PARAM_FLAG_VALID_CLEARING:    u8 = 3;
+pub const BOOT_PARAM_INFO_ACK:               u8 = 4;
+pub const BOOT_PARAM_BOOT_FLAGS:             u8 = 5;
+pub const BOOT_PARAM_INITIATOR_INFO:         u8 = 6;
+pub const BOOT_PARAM_INITIATOR_MAILBOX:      u8 = 7;
+
+/* boot device, bits [5:2] of the second boot flags byte */
+pub const BOOT_DEVICE_NONE:       u8 = 0x00;
+pub const BOOT_DEVICE_PXE:        u8 = 0x04;
+pub const BOOT_DEVICE_DISK:       u8 = 0x08;
+pub const BOOT_DEVICE_DISK_SAFE:  u8 = 0x0c;
+pub const BOOT_DEVICE_DIAG:       u8 = 0x10;
+pub const BOOT_DEVICE_CDROM:      u8 = 0x14;
+pub const BOOT_DEVICE_BIOS_SETUP: u8 = 0x18;
+pub const BOOT_DEVICE_FLOPPY:     u8 = 0x3c;
+
+/* first boot flags byte */
+pub const BOOT_FLAGS_VALID:      u8 = 0b10000000;
+pub const BOOT_FLAGS_PERSISTENT: u8 = 0b01000000;
+pub const BOOT_FLAGS_EFI:        u8 = 0b00100000;
+
+/* 
+ * Data of the boot flags parameter, without persistent the override only
+ * applies to the next boot.
+ */
+pub fn boot_flags(device: u8, persistent: bool, efi: bool) -> [u8; 5] {
+    let mut flags = BOOT_FLAGS_VALID;
+    if persistent { flags |= BOOT_FLAGS_PERSISTENT; }
+    if efi { flags |= BOOT_FLAGS_EFI; }
+    [flags, device, 0, 0, 0]
+}
+
+/* bit 7 of `parameter_selector` marks the parameter invalid or locked */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetSystemBootOptionsRequest<'a> {
+    pub parameter_selector: u8,
+    pub data: &'a [u8]
+}
+
+impl<'a> SetSystemBootOptionsRequest<'a> {
+    /* set the boot flags, `flags` is usually from `boot_flags` */
+    pub fn boot_flags(flags: &'a [u8; 5]) -> SetSystemBootOptionsRequest<'a> {
+        SetSystemBootOptionsRequest { parameter_selector: BOOT_PARAM_BOOT_FLAGS, data: flags }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetSystemBootOptionsResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSystemBootOptionsRequest {
+    pub parameter_selector: u8,
+    pub set_selector: u8,
+    pub block_selector: u8
+}
+
+/* `parameter_valid` holds the selector in bits [6:0], bit 7 set if invalid */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSystemBootOptionsResponse<'a> {
+    pub parameter_version: u8,
+    pub parameter_valid: u8,
+    pub data: &'a [u8]
+}
+
+impl GetSystemBootOptionsResponse<'_> {
+    pub fn parameter_selector(&self) -> u8 {
+        self.parameter_valid & 0b01111111
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.parameter_valid & 0b10000000 == 0
+    }
+}
+
+pub const CHASSIS_CAP_INTRUSION_SENSOR:  u8 = 0b00000001;
+pub const CHASSIS_CAP_FRONT_PANEL_LOCK:  u8 = 0b00000010;
+pub const CHASSIS_CAP_DIAG_INTERRUPT:    u8 = 0b00000100;
+pub const CHASSIS_CAP_POWER_INTERLOCK:   u8 = 0b00001000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetChassisCapabilitiesRequest {}
+
+/* slave addresses of the devices backing the chassis */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetChassisCapabilitiesResponse {
+    pub capabilities_flags: u8,
+    pub fru_device_address: u8,
+    pub sdr_device_address: u8,
+    pub sel_device_address: u8,
+    pub system_mgmt_device_address: u8,
+    /* left out by BMCs without a bridge device */
+    #[bytes_serialize(trailing)]
+    pub bridge_device_address: Option<u8>
+}
+
+impl GetChassisCapabilitiesResponse {
+    pub fn provides_intrusion_sensor(&self) -> bool {
+        self.capabilities_flags & CHASSIS_CAP_INTRUSION_SENSOR != 0
+    }
+
+    pub fn provides_power_interlock(&self) -> bool {
+        self.capabilities_flags & CHASSIS_CAP_POWER_INTERLOCK != 0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetPohCounterRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetPohCounterResponse {
+    pub minutes_per_count: u8,
+
+    #[bytes_serialize(endian = "le")]
+    pub counter_reading: u32
+}
+
+impl GetPohCounterResponse {
+    /* total powered on time */
+    pub fn minutes(&self) -> u64 {
+        u64::from(self.minutes_per_count) * u64::from(self.counter_reading)
+    }
+}
+
+/* bits of `additional_device_support` */
+pub const DEVICE_SUPPORT_SENSOR:          u8 = 0b00000001;
+pub const DEVICE_SUPPORT_SDR_REPOSITORY:  u8 = 0b00000010;
+pub const DEVICE_SUPPORT_SEL:             u8 = 0b00000100;
+pub const DEVICE_SUPPORT_FRU_INVENTORY:   u8 = 0b00001000;
+pub const DEVICE_SUPPORT_IPMB_EVENT_RECV: u8 = 0b00010000;
+pub const DEVICE_SUPPORT_IPMB_EVENT_GEN:  u8 = 0b00100000;
+pub const DEVICE_SUPPORT_BRIDGE:          u8 = 0b01000000;
+pub const DEVICE_SUPPORT_CHASSIS:         u8 = 0b10000000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetDeviceIdRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetDeviceIdResponse<'a> {
+    pub device_id: u8,
+    pub device_revision: u8,
+    /* bit 7 set while a firmware update or self initialization is in progress */
+    pub firmware_rev1: u8,
+    /* BCD minor revision */
+    pub firmware_rev2: u8,
+    /* BCD, minor revision in bits [7:4] */
+    pub ipmi_version: u8,
+    pub additional_device_support: u8,
+    pub manufacturer_id: [u8; 3],
+
+    #[bytes_serialize(endian = "le")]
+    pub product_id: u16,
+
+    /* the optional 4 bytes auxiliary firmware revision */
+    pub aux_firmware_rev: &'a [u8]
+}
+
+impl GetDeviceIdResponse<'_> {
+    /* the 20 bits IANA enterprise number */
+    pub fn manufacturer(&self) -> u32 {
+        crate::ipmi::oem::iana_from_3(self.manufacturer_id) & 0x000f_ffff
+    }
+
+    pub fn device_available(&self) -> bool {
+        self.firmware_rev1 & 0b10000000 == 0
+    }
+
+    pub fn supports_sensor(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_SENSOR != 0
+    }
+
+    pub fn supports_sdr_repository(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_SDR_REPOSITORY != 0
+    }
+
+    pub fn supports_sel(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_SEL != 0
+    }
+
+    pub fn supports_fru_inventory(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_FRU_INVENTORY != 0
+    }
+
+    pub fn supports_ipmb_event_receiver(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_IPMB_EVENT_RECV != 0
+    }
+
+    pub fn supports_ipmb_event_generator(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_IPMB_EVENT_GEN != 0
+    }
+
+    pub fn supports_bridge(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_BRIDGE != 0
+    }
+
+    pub fn supports_chassis(&self) -> bool {
+        self.additional_device_support & DEVICE_SUPPORT_CHASSIS != 0
+    }
+}
+
+/* restarts the BMC as from power up, including its self test */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ColdResetRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ColdResetResponse {}
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+/* resets the BMC without touching the system, sessions may be closed */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct WarmResetRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct WarmResetResponse {}
+
+pub const SELF_TEST_PASSED:          u8 = 0x55;
+pub const SELF_TEST_NOT_IMPLEMENTED: u8 = 0x56;
+/* `detail` tells which devices or data are corrupted or inaccessible */
+pub const SELF_TEST_CORRUPTED:       u8 = 0x57;
+/* `detail` holds the device specific failure */
+pub const SELF_TEST_FATAL_HARDWARE:  u8 = 0x58;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelfTestResultsRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelfTestResultsResponse {
+    pub result: u8,
+    pub detail: u8
+}
+
+impl GetSelfTestResultsResponse {
+    pub fn passed(&self) -> bool {
+        self.result == SELF_TEST_PASSED
+    }
+
+    pub fn not_implemented(&self) -> bool {
+        self.result == SELF_TEST_NOT_IMPLEMENTED
+    }
+}
+
+/* system power state, bits [6:0] of `system_power_state` */
+pub const ACPI_SYSTEM_S0_G0:       u8 = 0x00;
+pub const ACPI_SYSTEM_S1:          u8 = 0x01;
+pub const ACPI_SYSTEM_S2:          u8 = 0x02;
+pub const ACPI_SYSTEM_S3:          u8 = 0x03;
+pub const ACPI_SYSTEM_S4:          u8 = 0x04;
+pub const ACPI_SYSTEM_S5_G2:       u8 = 0x05;
+pub const ACPI_SYSTEM_S4_S5:       u8 = 0x06;
+pub const ACPI_SYSTEM_G3:          u8 = 0x07;
+pub const ACPI_SYSTEM_SLEEPING:    u8 = 0x08;
+pub const ACPI_SYSTEM_G1_SLEEPING: u8 = 0x09;
+pub const ACPI_SYSTEM_OVERRIDE:    u8 = 0x0a;
+pub const ACPI_SYSTEM_LEGACY_ON:   u8 = 0x20;
+pub const ACPI_SYSTEM_LEGACY_OFF:  u8 = 0x21;
+
+/* device power state, bits [6:0] of `device_power_state` */
+pub const ACPI_DEVICE_D0: u8 = 0x00;
+pub const ACPI_DEVICE_D1: u8 = 0x01;
+pub const ACPI_DEVICE_D2: u8 = 0x02;
+pub const ACPI_DEVICE_D3: u8 = 0x03;
+
+pub const ACPI_STATE_UNKNOWN:   u8 = 0x2a;
+pub const ACPI_STATE_NO_CHANGE: u8 = 0x7f;
+/* bit 7 of either state byte of Set ACPI Power State, update that state */
+pub const ACPI_STATE_SET:       u8 = 0x80;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetAcpiPowerStateRequest {
+    pub system_power_state: u8,
+    pub device_power_state: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetAcpiPowerStateResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetAcpiPowerStateRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetAcpiPowerStateResponse {
+    pub system_power_state: u8,
+    pub device_power_state: u8
+}
+
+impl GetAcpiPowerStateResponse {
+    pub fn system_state(&self) -> u8 {
+        self.system_power_state & 0x7f
+    }
+
+    pub fn device_state(&self) -> u8 {
+        self.device_power_state & 0x7f
+    }
+}
+
+/* timeout action, bits [2:0] of `timer_actions` */
+pub const WATCHDOG_ACTION_NONE:        u8 = 0x00;
+pub const WATCHDOG_ACTION_HARD_RESET:  u8 = 0x01;
+pub const WATCHDOG_ACTION_POWER_DOWN:  u8 = 0x02;
+pub const WATCHDOG_ACTION_POWER_CYCLE: u8 = 0x03;
+
+/* pre-timeout interrupt, bits [6:4] of `timer_actions` */
+pub const WATCHDOG_PRE_TIMEOUT_NONE: u8 = 0x00;
+pub const WATCHDOG_PRE_TIMEOUT_SMI:  u8 = 0x10;
+pub const WATCHDOG_PRE_TIMEOUT_NMI:  u8 = 0x20;
+pub const WATCHDOG_PRE_TIMEOUT_MSG:  u8 = 0x30;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ResetWatchdogTimerRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ResetWatchdogTimerResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetWatchdogTimerRequest {
+    pub timer_use: u8,
+    pub timer_actions: u8,
+    pub pre_timeout_interval: u8,
+    pub timer_use_expiration_flags: u8,
+
+    /* in 100ms */
+    #[bytes_serialize(endian = "le")]
+    pub initial_countdown: u16
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetWatchdogTimerResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetWatchdogTimerRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetWatchdogTimerResponse {
+    pub timer_use: u8,
+    pub timer_actions: u8,
+    pub pre_timeout_interval: u8,
+    pub timer_use_expiration_flags: u8,
+
+    #[bytes_serialize(endian = "le")]
+    pub initial_countdown: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub present_countdown: u16
+}
+
+impl GetWatchdogTimerResponse {
+    pub fn timeout_action(&self) -> u8 {
+        self.timer_actions & 0b00000111
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.timer_use & 0b01000000 == 0b01000000
+    }
+}
+
+/* 
+ * The channel byte of Send Message:
+ *   [7:6] tracking operation 
+ *   [5]   send with encryption
+ *   [4]   send with authentication
+ *   [3:0] channel number
+ *
+ * With tracking, the BMC records the request and routes the response of the 
+ * bridged request back to the requester. No tracking is meant for requests 
+ * that expect no response, or for responses being sent, raw sends the
+ * message as-is for testing.
+ */
+pub const SEND_MSG_TRACKING_NONE:    u8 = 0b00000000;
+pub const SEND_MSG_TRACKING_REQUEST: u8 = 0b01000000;
+pub const SEND_MSG_TRACKING_RAW:     u8 = 0b10000000;
+pub const SEND_MSG_ENCRYPTION:       u8 = 0b00100000;
+pub const SEND_MSG_AUTHENTICATION:   u8 = 0b00010000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SendMessageRequest<'a> {
+    pub channel: u8,
+    pub message: &'a [u8]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SendMessageResponse<'a> {
+    pub data: &'a [u8]
+}
+
+impl<'a> SendMessageRequest<'a> {
+    /* serialize `msg` into `buf` and use it as the bridged message */
+    pub fn wrap(channel: u8, msg: &IpmiMessage, buf: &'a mut [u8])
+        -> Result<SendMessageRequest<'a>, Error>
+    {
+        let size = msg.size();
+        msg.write_to_slice(buf, true)?;
+        let buf: &'a [u8] = buf;
+        Ok(SendMessageRequest { channel, message: &buf[..size] })
+    }
+
+    pub fn channel_number(&self) -> u8 {
+        self.channel & 0b00001111
+    }
+
+    pub fn tracking(&self) -> u8 {
+        self.channel & 0b11000000
+    }
+
+    /* decode the bridged message, which can be a Send Message itself */
+    pub fn inner(&self, strict: bool) -> Result<IpmiMessage<'a>, Error> {
+        IpmiMessage::from_bytes(self.message, strict)
+    }
+}
+
+/* `flags` bits of Get Message Flags and Clear Message Flags */
+pub const MSG_FLAG_RECEIVE_QUEUE:       u8 = 0b00000001;
+pub const MSG_FLAG_EVENT_BUFFER:        u8 = 0b00000010;
+pub const MSG_FLAG_WATCHDOG_PRETIMEOUT: u8 = 0b00001000;
+pub const MSG_FLAG_OEM:                 u8 = 0b11100000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetMessageFlagsRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetMessageFlagsResponse {
+    pub flags: u8
+}
+
+impl GetMessageFlagsResponse {
+    pub fn receive_queue_available(&self) -> bool {
+        self.flags & MSG_FLAG_RECEIVE_QUEUE != 0
+    }
+
+    pub fn event_buffer_full(&self) -> bool {
+        self.flags & MSG_FLAG_EVENT_BUFFER != 0
+    }
+
+    pub fn watchdog_pretimeout(&self) -> bool {
+        self.flags & MSG_FLAG_WATCHDOG_PRETIMEOUT != 0
+    }
+}
+
+/* set bits in `flags` clear the corresponding flags and buffers */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ClearMessageFlagsRequest {
+    pub flags: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ClearMessageFlagsResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetMessageRequest {}
+
+/* 
+ * The channel byte of Get Message:
+ *   [7:4] inferred privilege level
+ *   [3:0] channel number
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetMessageResponse<'a> {
+    pub channel: u8,
+    pub data: &'a [u8]
+}
+
+impl GetMessageResponse<'_> {
+    pub fn channel_number(&self) -> u8 {
+        self.channel & 0b00001111
+    }
+
+    pub fn privilege_level(&self) -> u8 {
+        self.channel >> 4
+    }
+}
+
+/* channel number as carried in the channel bytes of commands */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ChannelNumber(pub u8);
+
+impl ChannelNumber {
+    pub const PRIMARY_IPMB: ChannelNumber = ChannelNumber(0x00);
+    /* the channel the request is received on */
+    pub const CURRENT: ChannelNumber = ChannelNumber(0x0e);
+    pub const SYSTEM_INTERFACE: ChannelNumber = ChannelNumber(0x0f);
+
+    /* 0x0c-0x0d are reserved */
+    pub fn is_valid(&self) -> bool {
+        matches!(self.0, 0x00..=0x0b | 0x0e | 0x0f)
+    }
+}
+
+impl From<u8> for ChannelNumber {
+    fn from(value: u8) -> ChannelNumber {
+        ChannelNumber(value)
+    }
+}
+
+impl From<ChannelNumber> for u8 {
+    fn from(value: ChannelNumber) -> u8 {
+        value.0
+    }
+}
+
+impl BytesSerializationSized for ChannelNumber {
+    fn size(&self) -> usize { 1 }
+}
+
+impl BytesSerializable for ChannelNumber {
+    fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
+    {
+        self.0.write_to_slice(slice, strict)
+    }
+}
+
+impl BytesDeserializable<'_> for ChannelNumber {
+    fn from_bytes(slice: &'_ [u8], strict: bool) -> Result<ChannelNumber, Error> {
+        u8::from_bytes(slice, strict).map(ChannelNumber)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct GetChannelAuthCapRequest {
     pub channel_number: u8,
     pub max_priv_level: u8
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+/* set in `channel_number` to get the IPMI 2.0 extended capabilities */
+pub const AUTH_CAP_REQUEST_IPMI2: u8 = 0b10000000;
+
+impl GetChannelAuthCapRequest {
+    pub fn new(channel: u8, max_priv: u8, request_ipmi2: bool) -> GetChannelAuthCapRequest {
+        let ipmi2 = if request_ipmi2 { AUTH_CAP_REQUEST_IPMI2 } else { 0 };
+        GetChannelAuthCapRequest { channel_number: (channel & 0b00001111) | ipmi2, max_priv_level: max_priv }
+    }
+
+    /* the channel number without the IPMI 2.0 bit */
+    pub fn channel(&self) -> u8 {
+        self.channel_number & 0b00001111
+    }
+
+    pub fn requests_ipmi2(&self) -> bool {
+        self.channel_number & AUTH_CAP_REQUEST_IPMI2 != 0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[bytes_serialize(size = 8)]
 pub struct GetChannelAuthCapResponse {
     pub channel_number: u8,
     pub auth_types: u8,
@@ -75,13 +744,184 @@ pub struct GetChannelAuthCapResponse {
     pub oem_aux: u8
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+/* `auth_types` bits, the authentication types enabled for the requested level */
+pub const AUTH_CAP_TYPE_IPMI2_EXT:         u8 = 0b10000000;
+pub const AUTH_CAP_TYPE_OEM:               u8 = 0b00100000;
+pub const AUTH_CAP_TYPE_STRAIGHT_PASSWORD: u8 = 0b00010000;
+pub const AUTH_CAP_TYPE_MD5:               u8 = 0b00000100;
+pub const AUTH_CAP_TYPE_MD2:               u8 = 0b00000010;
+pub const AUTH_CAP_TYPE_NONE:              u8 = 0b00000001;
+
+/* `auth_caps` bits, note the per message and user level bits mean disabled */
+pub const AUTH_CAP_KG_CONFIGURED:         u8 = 0b00100000;
+pub const AUTH_CAP_PER_MSG_AUTH_DISABLED: u8 = 0b00010000;
+pub const AUTH_CAP_USER_AUTH_DISABLED:    u8 = 0b00001000;
+pub const AUTH_CAP_NON_NULL_USERNAMES:    u8 = 0b00000100;
+pub const AUTH_CAP_NULL_USERNAMES:        u8 = 0b00000010;
+pub const AUTH_CAP_ANONYMOUS_LOGIN:       u8 = 0b00000001;
+
+/* `ipmi2_ext` bits */
+pub const AUTH_CAP_EXT_IPMI2:  u8 = 0b00000010;
+pub const AUTH_CAP_EXT_IPMI15: u8 = 0b00000001;
+
+impl GetChannelAuthCapResponse {
+    pub fn supports_none(&self) -> bool {
+        self.auth_types & AUTH_CAP_TYPE_NONE != 0
+    }
+
+    pub fn supports_md2(&self) -> bool {
+        self.auth_types & AUTH_CAP_TYPE_MD2 != 0
+    }
+
+    pub fn supports_md5(&self) -> bool {
+        self.auth_types & AUTH_CAP_TYPE_MD5 != 0
+    }
+
+    pub fn supports_straight_password(&self) -> bool {
+        self.auth_types & AUTH_CAP_TYPE_STRAIGHT_PASSWORD != 0
+    }
+
+    pub fn supports_oem_auth(&self) -> bool {
+        self.auth_types & AUTH_CAP_TYPE_OEM != 0
+    }
+
+    pub fn per_message_auth_enabled(&self) -> bool {
+        self.auth_caps & AUTH_CAP_PER_MSG_AUTH_DISABLED == 0
+    }
+
+    pub fn user_level_auth_enabled(&self) -> bool {
+        self.auth_caps & AUTH_CAP_USER_AUTH_DISABLED == 0
+    }
+
+    pub fn kg_configured(&self) -> bool {
+        self.auth_caps & AUTH_CAP_KG_CONFIGURED != 0
+    }
+
+    pub fn anonymous_login_enabled(&self) -> bool {
+        self.auth_caps & AUTH_CAP_ANONYMOUS_LOGIN != 0
+    }
+
+    /* only meaningful when the BMC reports extended capabilities */
+    pub fn supports_ipmi2(&self) -> bool {
+        self.auth_types & AUTH_CAP_TYPE_IPMI2_EXT != 0 && self.ipmi2_ext & AUTH_CAP_EXT_IPMI2 != 0
+    }
+
+    /* IANA number of the OEM, for OEM authentication types */
+    pub fn manufacturer(&self) -> u32 {
+        crate::ipmi::oem::iana_from_3(self.oem_id)
+    }
+
+    /* 
+     * start of a response with nothing enabled, in particular per message
+     * and user level authentication start disabled
+     */
+    pub fn builder(channel_number: u8) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse {
+            channel_number,
+            auth_types: 0,
+            auth_caps: AUTH_CAP_PER_MSG_AUTH_DISABLED | AUTH_CAP_USER_AUTH_DISABLED,
+            ipmi2_ext: 0,
+            oem_id: [0; 3],
+            oem_aux: 0
+        }
+    }
+
+    pub fn with_none(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_types: self.auth_types | AUTH_CAP_TYPE_NONE, ..self }
+    }
+
+    pub fn with_md2(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_types: self.auth_types | AUTH_CAP_TYPE_MD2, ..self }
+    }
+
+    pub fn with_md5(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_types: self.auth_types | AUTH_CAP_TYPE_MD5, ..self }
+    }
+
+    pub fn with_straight_password(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_types: self.auth_types | AUTH_CAP_TYPE_STRAIGHT_PASSWORD, ..self }
+    }
+
+    pub fn with_oem_auth(self, iana: u32, oem_aux: u8) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse {
+            auth_types: self.auth_types | AUTH_CAP_TYPE_OEM,
+            oem_id: crate::ipmi::oem::iana_to_3(iana),
+            oem_aux,
+            ..self
+        }
+    }
+
+    pub fn with_per_message_auth(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_caps: self.auth_caps & !AUTH_CAP_PER_MSG_AUTH_DISABLED, ..self }
+    }
+
+    pub fn with_user_level_auth(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_caps: self.auth_caps & !AUTH_CAP_USER_AUTH_DISABLED, ..self }
+    }
+
+    pub fn with_kg_configured(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_caps: self.auth_caps | AUTH_CAP_KG_CONFIGURED, ..self }
+    }
+
+    pub fn with_anonymous_login(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_caps: self.auth_caps | AUTH_CAP_ANONYMOUS_LOGIN, ..self }
+    }
+
+    pub fn with_null_usernames(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_caps: self.auth_caps | AUTH_CAP_NULL_USERNAMES, ..self }
+    }
+
+    pub fn with_non_null_usernames(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse { auth_caps: self.auth_caps | AUTH_CAP_NON_NULL_USERNAMES, ..self }
+    }
+
+    /* report extended capabilities, both IPMI 1.5 and 2.0 */
+    pub fn with_ipmi2(self) -> GetChannelAuthCapResponse {
+        GetChannelAuthCapResponse {
+            auth_types: self.auth_types | AUTH_CAP_TYPE_IPMI2_EXT,
+            ipmi2_ext: self.ipmi2_ext | AUTH_CAP_EXT_IPMI2 | AUTH_CAP_EXT_IPMI15,
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct GetSessionChallengeRequest {
     pub auth_type: u8,
     pub username: [u8;16]
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+/* zero pad `name`, which can be at most 16 bytes */
+fn pad_user_name(name: &str) -> Result<[u8; 16], Error> {
+    let bytes = name.as_bytes();
+    if bytes.len() > 16 {
+        return Err(Error::PayloadTooLarge);
+    }
+
+    let mut padded = [0; 16];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    Ok(padded)
+}
+
+/* the inverse of `pad_user_name` */
+fn trim_user_name(name: &[u8; 16]) -> Result<&str, core::str::Utf8Error> {
+    let len = name.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    core::str::from_utf8(&name[..len])
+}
+
+impl GetSessionChallengeRequest {
+    /* zero pad `username`, which can be at most 16 bytes */
+    pub fn new(auth_type: u8, username: &str) -> Result<GetSessionChallengeRequest, Error> {
+        Ok(GetSessionChallengeRequest { auth_type, username: pad_user_name(username)? })
+    }
+
+    /* the username without the zero padding */
+    pub fn username_str(&self) -> Result<&str, core::str::Utf8Error> {
+        trim_user_name(&self.username)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct GetSessionChallengeResponse
 {
     #[bytes_serialize(endian = "le")]
@@ -89,7 +929,7 @@ pub struct GetSessionChallengeResponse
     pub challenge_dat: [u8;16]
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct ActivateSessionRequest {
     pub auth_type: u8,
     pub max_priv_level: u8,
@@ -98,7 +938,24 @@ pub struct ActivateSessionRequest {
     pub init_outbound_seq: u32
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+impl ActivateSessionRequest {
+    /* 
+     * answer the challenge of `challenge`, the request is sent in a packet
+     * carrying its temporary session id
+     */
+    pub fn from_challenge(auth_type: u8, max_priv_level: u8, challenge: &GetSessionChallengeResponse,
+                          init_outbound_seq: u32) -> ActivateSessionRequest
+    {
+        ActivateSessionRequest {
+            auth_type,
+            max_priv_level,
+            challenge_string: challenge.challenge_dat,
+            init_outbound_seq
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct ActivateSessionResponse {
     pub auth_type: u8,
 
@@ -111,12 +968,592 @@ pub struct ActivateSessionResponse {
     pub max_priv_level: u8
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct SetSessionPrivLevelRequest {
-    pub priv_level: u8
+    pub priv_level: PrivLevel
 }
 
-#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
 pub struct SetSessionPrivLevelResponse {
     pub priv_level: u8
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct CloseSessionRequest {
+    #[bytes_serialize(endian = "le")]
+    pub session_id: u32,
+    /* only sent with a zero session id, to close a session by its handle */
+    #[bytes_serialize(trailing)]
+    pub session_handle: Option<u8>
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct CloseSessionResponse {}
+
+/* access mode, bits [2:0] of `channel_access` */
+pub const CHANNEL_ACCESS_MODE_DISABLED:  u8 = 0x00;
+pub const CHANNEL_ACCESS_MODE_PRE_BOOT:  u8 = 0x01;
+pub const CHANNEL_ACCESS_MODE_ALWAYS:    u8 = 0x02;
+pub const CHANNEL_ACCESS_MODE_SHARED:    u8 = 0x03;
+
+pub const CHANNEL_ACCESS_ALERTING_DISABLED:     u8 = 0b00100000;
+pub const CHANNEL_ACCESS_PER_MSG_AUTH_DISABLED: u8 = 0b00010000;
+pub const CHANNEL_ACCESS_USER_AUTH_DISABLED:    u8 = 0b00001000;
+
+/* 
+ * which copy of the settings to get, or to set in bits [7:6] of the access
+ * and privilege limit bytes of Set Channel Access
+ */
+pub const CHANNEL_ACCESS_NON_VOLATILE: u8 = 0b01000000;
+pub const CHANNEL_ACCESS_VOLATILE:     u8 = 0b10000000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetChannelAccessRequest {
+    pub channel: u8,
+    pub access_type: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetChannelAccessResponse {
+    pub channel_access: u8,
+    pub priv_limit: u8
+}
+
+impl GetChannelAccessResponse {
+    pub fn access_mode(&self) -> u8 {
+        self.channel_access & 0b00000111
+    }
+
+    pub fn is_alerting_disabled(&self) -> bool {
+        self.channel_access & CHANNEL_ACCESS_ALERTING_DISABLED != 0
+    }
+
+    pub fn is_per_msg_auth_disabled(&self) -> bool {
+        self.channel_access & CHANNEL_ACCESS_PER_MSG_AUTH_DISABLED != 0
+    }
+
+    pub fn is_user_auth_disabled(&self) -> bool {
+        self.channel_access & CHANNEL_ACCESS_USER_AUTH_DISABLED != 0
+    }
+
+    /* None if the limit is not one of the levels defined by the spec */
+    pub fn priv_level_limit(&self) -> Option<PrivLevel> {
+        PrivLevel::from_u8(self.priv_limit & 0b00001111)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetChannelAccessRequest {
+    pub channel: u8,
+    pub channel_access: u8,
+    pub priv_limit: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetChannelAccessResponse {}
+
+/* medium of a channel, bits [6:0] of the Get Channel Info medium byte */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ChannelMedium {
+    Ipmb,
+    Icmb10,
+    Icmb09,
+    Lan8023,
+    SerialModem,
+    OtherLan,
+    PciSmbus,
+    Smbus1x,
+    Smbus20,
+    Usb1x,
+    Usb2x,
+    /* KCS, SMIC, BT or SSIF */
+    SystemInterface
+}
+
+impl ChannelMedium {
+    /* None for the reserved and OEM medium numbers */
+    pub fn from_u8(medium: u8) -> Option<ChannelMedium> {
+        match medium {
+            0x01 => Some(ChannelMedium::Ipmb),
+            0x02 => Some(ChannelMedium::Icmb10),
+            0x03 => Some(ChannelMedium::Icmb09),
+            0x04 => Some(ChannelMedium::Lan8023),
+            0x05 => Some(ChannelMedium::SerialModem),
+            0x06 => Some(ChannelMedium::OtherLan),
+            0x07 => Some(ChannelMedium::PciSmbus),
+            0x08 => Some(ChannelMedium::Smbus1x),
+            0x09 => Some(ChannelMedium::Smbus20),
+            0x0a => Some(ChannelMedium::Usb1x),
+            0x0b => Some(ChannelMedium::Usb2x),
+            0x0c => Some(ChannelMedium::SystemInterface),
+            _ => None
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            ChannelMedium::Ipmb            => 0x01,
+            ChannelMedium::Icmb10          => 0x02,
+            ChannelMedium::Icmb09          => 0x03,
+            ChannelMedium::Lan8023         => 0x04,
+            ChannelMedium::SerialModem     => 0x05,
+            ChannelMedium::OtherLan        => 0x06,
+            ChannelMedium::PciSmbus        => 0x07,
+            ChannelMedium::Smbus1x         => 0x08,
+            ChannelMedium::Smbus20         => 0x09,
+            ChannelMedium::Usb1x           => 0x0a,
+            ChannelMedium::Usb2x           => 0x0b,
+            ChannelMedium::SystemInterface => 0x0c
+        }
+    }
+}
+
+/* protocol of a channel, bits [4:0] of the Get Channel Info protocol byte */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ChannelProtocol {
+    Ipmb,
+    Icmb,
+    Smbus,
+    Kcs,
+    Smic,
+    Bt10,
+    Bt15,
+    TMode,
+    /* 0x1c-0x1f */
+    Oem(u8)
+}
+
+impl ChannelProtocol {
+    /* None for the reserved protocol numbers */
+    pub fn from_u8(protocol: u8) -> Option<ChannelProtocol> {
+        match protocol {
+            0x01 => Some(ChannelProtocol::Ipmb),
+            0x02 => Some(ChannelProtocol::Icmb),
+            0x04 => Some(ChannelProtocol::Smbus),
+            0x05 => Some(ChannelProtocol::Kcs),
+            0x06 => Some(ChannelProtocol::Smic),
+            0x07 => Some(ChannelProtocol::Bt10),
+            0x08 => Some(ChannelProtocol::Bt15),
+            0x09 => Some(ChannelProtocol::TMode),
+            0x1c..=0x1f => Some(ChannelProtocol::Oem(protocol)),
+            _ => None
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            ChannelProtocol::Ipmb  => 0x01,
+            ChannelProtocol::Icmb  => 0x02,
+            ChannelProtocol::Smbus => 0x04,
+            ChannelProtocol::Kcs   => 0x05,
+            ChannelProtocol::Smic  => 0x06,
+            ChannelProtocol::Bt10  => 0x07,
+            ChannelProtocol::Bt15  => 0x08,
+            ChannelProtocol::TMode => 0x09,
+            ChannelProtocol::Oem(protocol) => *protocol
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetChannelInfoRequest {
+    pub channel: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[bytes_serialize(size = 9)]
+pub struct GetChannelInfoResponse {
+    pub channel: u8,
+    pub medium_type: u8,
+    pub protocol_type: u8,
+    /* [7:6] session support, [5:0] number of active sessions */
+    pub session_info: u8,
+    pub vendor_id: [u8; 3],
+    pub aux_info: [u8; 2]
+}
+
+impl GetChannelInfoResponse {
+    pub fn channel_number(&self) -> ChannelNumber {
+        ChannelNumber(self.channel & 0b00001111)
+    }
+
+    /* None if the raw `medium_type` is reserved or OEM */
+    pub fn medium(&self) -> Option<ChannelMedium> {
+        ChannelMedium::from_u8(self.medium_type & 0b01111111)
+    }
+
+    /* None if the raw `protocol_type` is reserved */
+    pub fn protocol(&self) -> Option<ChannelProtocol> {
+        ChannelProtocol::from_u8(self.protocol_type & 0b00011111)
+    }
+
+    pub fn active_sessions(&self) -> u8 {
+        self.session_info & 0b00111111
+    }
+}
+
+/* `access` bits of Get User Access, the privilege limit is in bits [3:0] */
+pub const USER_ACCESS_CALLBACK_ONLY:  u8 = 0b01000000;
+pub const USER_ACCESS_LINK_AUTH:      u8 = 0b00100000;
+pub const USER_ACCESS_IPMI_MESSAGING: u8 = 0b00010000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetUserAccessRequest {
+    pub channel: u8,
+    pub user_id: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetUserAccessResponse {
+    pub max_user_ids: u8,
+    pub enabled_user_count: u8,
+    pub fixed_name_count: u8,
+    pub access: u8
+}
+
+impl GetUserAccessResponse {
+    /* the counts only use bits [5:0] */
+    pub fn max_users(&self) -> u8 {
+        self.max_user_ids & 0b00111111
+    }
+
+    pub fn enabled_users(&self) -> u8 {
+        self.enabled_user_count & 0b00111111
+    }
+
+    pub fn fixed_names(&self) -> u8 {
+        self.fixed_name_count & 0b00111111
+    }
+
+    pub fn is_callback_only(&self) -> bool {
+        self.access & USER_ACCESS_CALLBACK_ONLY != 0
+    }
+
+    pub fn link_auth_enabled(&self) -> bool {
+        self.access & USER_ACCESS_LINK_AUTH != 0
+    }
+
+    pub fn ipmi_messaging_enabled(&self) -> bool {
+        self.access & USER_ACCESS_IPMI_MESSAGING != 0
+    }
+
+    /* None if the limit is not one of the levels defined by the spec */
+    pub fn priv_level_limit(&self) -> Option<PrivLevel> {
+        PrivLevel::from_u8(self.access & 0b00001111)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetUserNameRequest {
+    pub user_id: u8,
+    pub name: [u8; 16]
+}
+
+impl SetUserNameRequest {
+    /* zero pad `name`, which can be at most 16 bytes */
+    pub fn new(user_id: u8, name: &str) -> Result<SetUserNameRequest, Error> {
+        Ok(SetUserNameRequest { user_id, name: pad_user_name(name)? })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetUserNameResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetUserNameRequest {
+    pub user_id: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetUserNameResponse {
+    pub name: [u8; 16]
+}
+
+impl GetUserNameResponse {
+    /* the name without the zero padding */
+    pub fn name_str(&self) -> Result<&str, core::str::Utf8Error> {
+        trim_user_name(&self.name)
+    }
+}
+
+/* bus type, bit [0] of the Master Write-Read channel byte */
+pub const I2C_BUS_PUBLIC:  u8 = 0;
+pub const I2C_BUS_PRIVATE: u8 = 1;
+
+/* 
+ * The channel byte of Master Write-Read:
+ *   [7:4] channel number
+ *   [3:1] bus ID
+ *   [0]   bus type
+ * `slave_addr` is the 7 bits address in bits [7:1]. `data` is written 
+ * before `read_count` bytes are read back.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct MasterWriteReadRequest<'a> {
+    pub channel: u8,
+    pub slave_addr: u8,
+    pub read_count: u8,
+    pub data: &'a [u8]
+}
+
+impl MasterWriteReadRequest<'_> {
+    pub fn channel_byte(channel: u8, bus_id: u8, bus_type: u8) -> u8 {
+        ((channel & 0b1111) << 4) | ((bus_id & 0b111) << 1) | (bus_type & 1)
+    }
+
+    pub fn channel_number(&self) -> u8 {
+        self.channel >> 4
+    }
+
+    pub fn bus_id(&self) -> u8 {
+        (self.channel >> 1) & 0b111
+    }
+
+    pub fn bus_type(&self) -> u8 {
+        self.channel & 1
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct MasterWriteReadResponse<'a> {
+    pub data: &'a [u8]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSdrRepositoryInfoRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSdrRepositoryInfoResponse {
+    pub sdr_version: u8,
+
+    #[bytes_serialize(endian = "le")]
+    pub record_count: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub free_space: u16,
+
+    pub most_recent_addition: IpmiTimestamp,
+    pub most_recent_erase: IpmiTimestamp,
+
+    pub operation_support: u8
+}
+
+/* the reservation id of partial Get SDR reads */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ReserveSdrRepositoryRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ReserveSdrRepositoryResponse {
+    #[bytes_serialize(endian = "le")]
+    pub reservation_id: u16
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSdrRequest {
+    #[bytes_serialize(endian = "le")]
+    pub reservation_id: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub record_id: u16,
+
+    pub offset: u8,
+    pub bytes_to_read: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSdrResponse<'a> {
+    #[bytes_serialize(endian = "le")]
+    pub next_record_id: u16,
+    pub data: &'a [u8]
+}
+
+impl GetSdrResponse<'_> {
+    /* the record read is the last one of the repository */
+    pub fn is_last(&self) -> bool {
+        self.next_record_id == crate::ipmi::sdr::SDR_LAST_RECORD
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelInfoRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelInfoResponse {
+    pub sel_version: u8,
+
+    #[bytes_serialize(endian = "le")]
+    pub entries: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub free_space: u16,
+
+    pub most_recent_addition: IpmiTimestamp,
+    pub most_recent_erase: IpmiTimestamp,
+
+    pub operation_support: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ReserveSelRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ReserveSelResponse {
+    #[bytes_serialize(endian = "le")]
+    pub reservation_id: u16
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelEntryRequest {
+    #[bytes_serialize(endian = "le")]
+    pub reservation_id: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub record_id: u16,
+
+    pub offset: u8,
+    pub bytes_to_read: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[bytes_serialize(size = 18)]
+pub struct GetSelEntryResponse {
+    #[bytes_serialize(endian = "le")]
+    pub next_record_id: u16,
+    pub record: [u8; 16]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct AddSelEntryRequest {
+    pub record: [u8; 16]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct AddSelEntryResponse {
+    #[bytes_serialize(endian = "le")]
+    pub record_id: u16
+}
+
+/* `clear_byte` of Clear SEL, guarding against accidental erasure */
+pub const SEL_CLEAR_MAGIC: [u8; 3] = *b"CLR";
+
+/* `action` of Clear SEL */
+pub const SEL_CLEAR_INITIATE:   u8 = 0xaa;
+pub const SEL_CLEAR_GET_STATUS: u8 = 0x00;
+
+/* `erasure_progress` of the Clear SEL response */
+pub const SEL_ERASURE_IN_PROGRESS: u8 = 0x00;
+pub const SEL_ERASURE_COMPLETED:   u8 = 0x01;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ClearSelRequest {
+    #[bytes_serialize(endian = "le")]
+    pub reservation_id: u16,
+    pub clear_byte: [u8; 3],
+    pub action: u8
+}
+
+impl ClearSelRequest {
+    /* `action` is SEL_CLEAR_INITIATE or SEL_CLEAR_GET_STATUS */
+    pub fn new(reservation_id: u16, action: u8) -> ClearSelRequest {
+        ClearSelRequest { reservation_id, clear_byte: SEL_CLEAR_MAGIC, action }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ClearSelResponse {
+    pub erasure_progress: u8
+}
+
+impl ClearSelResponse {
+    pub fn is_completed(&self) -> bool {
+        self.erasure_progress & 0x0f == SEL_ERASURE_COMPLETED
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelTimeRequest {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetSelTimeResponse {
+    pub timestamp: IpmiTimestamp
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetSelTimeRequest {
+    pub timestamp: IpmiTimestamp
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetSelTimeResponse {}
+
+pub const LAN_PARAM_IP_ADDRESS:         u8 = 3;
+pub const LAN_PARAM_MAC_ADDRESS:        u8 = 5;
+pub const LAN_PARAM_SUBNET_MASK:        u8 = 6;
+pub const LAN_PARAM_DEFAULT_GATEWAY:    u8 = 12;
+pub const LAN_PARAM_DEFAULT_GATEWAY_MAC: u8 = 13;
+
+/* set in the channel byte to only get the parameter revision */
+pub const LAN_PARAM_REVISION_ONLY: u8 = 0b10000000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetLanConfigParamRequest {
+    pub channel: u8,
+    pub parameter_selector: u8,
+    pub set_selector: u8,
+    pub block_selector: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetLanConfigParamResponse<'a> {
+    pub parameter_version: u8,
+    pub data: &'a [u8]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetLanConfigParamRequest<'a> {
+    pub channel: u8,
+    pub parameter_selector: u8,
+    pub data: &'a [u8]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetLanConfigParamResponse {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetFruInventoryAreaInfoRequest {
+    pub fru_device_id: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct GetFruInventoryAreaInfoResponse {
+    #[bytes_serialize(endian = "le")]
+    pub size: u16,
+    pub access: u8
+}
+
+impl GetFruInventoryAreaInfoResponse {
+    /* bit 0 of `access` set means the device is accessed by words */
+    pub fn is_word_access(&self) -> bool {
+        self.access & 0b00000001 == 0b00000001
+    }
+
+    pub fn is_byte_access(&self) -> bool {
+        !self.is_word_access()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ReadFruDataRequest {
+    pub fru_device_id: u8,
+
+    #[bytes_serialize(endian = "le")]
+    pub offset: u16,
+
+    pub count: u8
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ReadFruDataResponse<'a> {
+    pub count_returned: u8,
+    pub data: &'a [u8]
+}
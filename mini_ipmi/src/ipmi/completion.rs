@@ -0,0 +1,111 @@
+/* completion code, the first byte of every IPMI response data */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct CompletionCode(pub u8);
+
+/* 
+ * Meaning of a command specific (0x80-0xbe) or OEM completion code, for a
+ * given request netfn and command.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct CompletionCodeMeaning {
+    pub netfn: u8,
+    pub cmd: u8,
+    pub code: u8,
+    pub meaning: &'static str
+}
+
+impl CompletionCode {
+    pub const SUCCESS:                  CompletionCode = CompletionCode(0x00);
+    pub const NODE_BUSY:                CompletionCode = CompletionCode(0xc0);
+    pub const INVALID_COMMAND:          CompletionCode = CompletionCode(0xc1);
+    pub const INVALID_FOR_LUN:          CompletionCode = CompletionCode(0xc2);
+    pub const TIMEOUT:                  CompletionCode = CompletionCode(0xc3);
+    pub const OUT_OF_SPACE:             CompletionCode = CompletionCode(0xc4);
+    pub const RESERVATION_CANCELLED:    CompletionCode = CompletionCode(0xc5);
+    pub const REQUEST_DATA_TRUNCATED:   CompletionCode = CompletionCode(0xc6);
+    pub const REQUEST_LENGTH_INVALID:   CompletionCode = CompletionCode(0xc7);
+    pub const REQUEST_LENGTH_EXCEEDED:  CompletionCode = CompletionCode(0xc8);
+    pub const PARAMETER_OUT_OF_RANGE:   CompletionCode = CompletionCode(0xc9);
+    pub const CANNOT_RETURN_BYTES:      CompletionCode = CompletionCode(0xca);
+    pub const NOT_PRESENT:              CompletionCode = CompletionCode(0xcb);
+    pub const INVALID_DATA_FIELD:       CompletionCode = CompletionCode(0xcc);
+    pub const ILLEGAL_FOR_SENSOR:       CompletionCode = CompletionCode(0xcd);
+    pub const RESPONSE_UNAVAILABLE:     CompletionCode = CompletionCode(0xce);
+    pub const DUPLICATED_REQUEST:       CompletionCode = CompletionCode(0xcf);
+    pub const SDR_IN_UPDATE_MODE:       CompletionCode = CompletionCode(0xd0);
+    pub const FIRMWARE_UPDATE_MODE:     CompletionCode = CompletionCode(0xd1);
+    pub const BMC_INITIALIZING:         CompletionCode = CompletionCode(0xd2);
+    pub const DESTINATION_UNAVAILABLE:  CompletionCode = CompletionCode(0xd3);
+    pub const INSUFFICIENT_PRIVILEGE:   CompletionCode = CompletionCode(0xd4);
+    pub const NOT_SUPPORTED_IN_STATE:   CompletionCode = CompletionCode(0xd5);
+    pub const SUBFUNCTION_DISABLED:     CompletionCode = CompletionCode(0xd6);
+    pub const UNSPECIFIED:              CompletionCode = CompletionCode(0xff);
+
+    pub fn is_success(&self) -> bool {
+        *self == Self::SUCCESS
+    }
+
+    /* text of the generic completion codes defined by the spec */
+    pub fn describe(&self) -> Option<&'static str> {
+        let text = match *self {
+            Self::SUCCESS                 => "Command completed normally",
+            Self::NODE_BUSY               => "Node busy",
+            Self::INVALID_COMMAND         => "Invalid command",
+            Self::INVALID_FOR_LUN         => "Command invalid for given LUN",
+            Self::TIMEOUT                 => "Timeout while processing command",
+            Self::OUT_OF_SPACE            => "Out of space",
+            Self::RESERVATION_CANCELLED   => "Reservation cancelled or invalid reservation ID",
+            Self::REQUEST_DATA_TRUNCATED  => "Request data truncated",
+            Self::REQUEST_LENGTH_INVALID  => "Request data length invalid",
+            Self::REQUEST_LENGTH_EXCEEDED => "Request data field length limit exceeded",
+            Self::PARAMETER_OUT_OF_RANGE  => "Parameter out of range",
+            Self::CANNOT_RETURN_BYTES     => "Cannot return number of requested data bytes",
+            Self::NOT_PRESENT             => "Requested sensor, data, or record not present",
+            Self::INVALID_DATA_FIELD      => "Invalid data field in request",
+            Self::ILLEGAL_FOR_SENSOR      => "Command illegal for specified sensor or record type",
+            Self::RESPONSE_UNAVAILABLE    => "Command response could not be provided",
+            Self::DUPLICATED_REQUEST      => "Cannot execute duplicated request",
+            Self::SDR_IN_UPDATE_MODE      => "SDR repository in update mode",
+            Self::FIRMWARE_UPDATE_MODE    => "Device in firmware update mode",
+            Self::BMC_INITIALIZING        => "BMC initialization in progress",
+            Self::DESTINATION_UNAVAILABLE => "Destination unavailable",
+            Self::INSUFFICIENT_PRIVILEGE  => "Insufficient privilege level",
+            Self::NOT_SUPPORTED_IN_STATE  => "Command not supported in present state",
+            Self::SUBFUNCTION_DISABLED    => "Sub-function disabled or unavailable",
+            Self::UNSPECIFIED             => "Unspecified error",
+            _ => return None
+        };
+        Some(text)
+    }
+
+    /* 
+     * Like `describe`, but consult `table` first for the meaning of the code
+     * in response to `netfn`/`cmd`. Either the request or the response netfn
+     * can be used.
+     */
+    pub fn describe_for(&self, netfn: u8, cmd: u8, table: &[CompletionCodeMeaning])
+        -> Option<&'static str>
+    {
+        let netfn = netfn & !1;
+
+        table.iter()
+            .find(|m| (m.netfn & !1) == netfn && m.cmd == cmd && m.code == self.0)
+            .map(|m| m.meaning)
+            .or_else(|| self.describe())
+    }
+}
+
+impl From<u8> for CompletionCode {
+    fn from(code: u8) -> CompletionCode {
+        CompletionCode(code)
+    }
+}
+
+impl core::fmt::Display for CompletionCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.describe() {
+            Some(text) => write!(f, "{} (0x{:02x})", text, self.0),
+            None => write!(f, "Completion code 0x{:02x}", self.0)
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use aes::Aes128;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::cipher::generic_array::GenericArray;
+
+use crate::ipmi::Error;
+
+pub const AES_CBC_128_BLOCK_SIZE: usize = 16;
+
+/* 
+ * Confidentiality of RMCP+ payloads with AES-CBC-128. On the wire the payload
+ * is the IV followed by the encrypted payload data, confidentiality pad bytes
+ * 1, 2, 3 ... and the pad length, padded up to a multiple of the block size.
+ * The key is the first 16 bytes of K2.
+ */
+
+/* size of the encrypted form of `payload_len` bytes, IV included */
+pub fn encrypted_len(payload_len: usize) -> usize {
+    let blocks = (payload_len + 1).div_ceil(AES_CBC_128_BLOCK_SIZE);
+    AES_CBC_128_BLOCK_SIZE + blocks * AES_CBC_128_BLOCK_SIZE
+}
+
+fn cipher(k2: &[u8]) -> Result<Aes128, Error> {
+    let key = k2.get(..16).ok_or(Error::InvalidConfiguration)?;
+    Ok(Aes128::new(GenericArray::from_slice(key)))
+}
+
+/* 
+ * Encrypt `payload` into `out` with the caller chosen `iv`, which must not
+ * be reused, return the number of bytes written
+ */
+pub fn encrypt_payload(k2: &[u8], iv: &[u8; 16], payload: &[u8], out: &mut [u8]) 
+    -> Result<usize, Error>
+{
+    let len = encrypted_len(payload.len());
+    let out = out.get_mut(..len).ok_or(Error::OutBufferTooSmall)?;
+    let cipher = cipher(k2)?;
+
+    let (head, data) = out.split_at_mut(AES_CBC_128_BLOCK_SIZE);
+    head.copy_from_slice(iv);
+
+    /* payload, pad bytes 1..=pad_len, pad_len */
+    let pad_len = data.len() - payload.len() - 1;
+    let (plain, trailer) = data.split_at_mut(payload.len());
+    plain.copy_from_slice(payload);
+    for (byte, n) in trailer.iter_mut().zip(1u8..) {
+        *byte = n;
+    }
+    if let Some(last) = trailer.last_mut() {
+        *last = pad_len as u8;
+    }
+
+    let mut prev = *iv;
+    for block in data.chunks_exact_mut(AES_CBC_128_BLOCK_SIZE) {
+        block.iter_mut().zip(prev.iter()).for_each(|(b, p)| *b ^= p);
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+        prev.copy_from_slice(block);
+    }
+
+    Ok(len)
+}
+
+/* 
+ * Decrypt `encrypted`, IV included, into `out` and return the length of the
+ * payload data, without the confidentiality trailer
+ */
+pub fn decrypt_payload(k2: &[u8], encrypted: &[u8], out: &mut [u8]) -> Result<usize, Error>
+{
+    let (iv, data) = encrypted.split_at_checked(AES_CBC_128_BLOCK_SIZE)
+        .ok_or(Error::PayloadTooSmall)?;
+
+    if data.is_empty() || data.len() % AES_CBC_128_BLOCK_SIZE != 0 {
+        return Err(Error::InvalidConfiguration);
+    }
+
+    let out = out.get_mut(..data.len()).ok_or(Error::OutBufferTooSmall)?;
+    let cipher = cipher(k2)?;
+
+    let mut prev = iv;
+    for (block, cipher_block) in out.chunks_exact_mut(AES_CBC_128_BLOCK_SIZE)
+        .zip(data.chunks_exact(AES_CBC_128_BLOCK_SIZE))
+    {
+        block.copy_from_slice(cipher_block);
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        block.iter_mut().zip(prev.iter()).for_each(|(b, p)| *b ^= p);
+        prev = cipher_block;
+    }
+
+    let (&pad_len, rest) = out.split_last().ok_or(Error::PayloadTooSmall)?;
+    let payload_len = rest.len().checked_sub(usize::from(pad_len))
+        .ok_or(Error::InvalidConfiguration)?;
+
+    /* pad bytes must read 1, 2, 3 ... */
+    let pad = rest.get(payload_len..).ok_or(Error::InvalidConfiguration)?;
+    if pad.iter().zip(1u8..).any(|(byte, n)| *byte != n) {
+        return Err(Error::InvalidConfiguration);
+    }
+
+    Ok(payload_len)
+}
@@ -0,0 +1,185 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ipmi::asf::AsfMessage;
+use crate::ipmi::ipmi::{Ipmi15Packet, IpmiData, IPMI_AUTH_TYPE_NONE};
+use crate::ipmi::rmcp::{RmcpContent, RmcpMessage, MSG_CLASS_ASF, MSG_CLASS_IPMI, MSG_CLASS_OEM};
+use crate::ipmi::*;
+
+/* 
+ * Human readable walk through the layers of a frame, ending at the first
+ * place it diverges from a valid frame if there is one.
+ */
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct DiagnosticReport {
+    pub steps: Vec<String>,
+    /* the error a lenient decode stops at, None if the frame decodes */
+    pub error: Option<Error>
+}
+
+impl DiagnosticReport {
+    fn note(&mut self, step: String) {
+        self.steps.push(step);
+    }
+
+    fn fail(mut self, step: String, error: Error) -> DiagnosticReport {
+        self.steps.push(step);
+        self.error = Some(error);
+        self
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl core::fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.steps.join("; "))
+    }
+}
+
+/* explain the structure of an RMCP frame and why it fails to decode, if it does */
+pub fn diagnose(bytes: &[u8]) -> DiagnosticReport
+{
+    let mut report = DiagnosticReport::default();
+
+    if bytes.len() < 4 {
+        return report.fail(
+            format!("RMCP header truncated, {} of 4 bytes", bytes.len()),
+            Error::PayloadTooSmall);
+    }
+
+    let (version, reserved, seqnum, class) = (bytes[0], bytes[1], bytes[2], bytes[3]);
+
+    match (version, reserved) {
+        (0x06, 0x00) => report.note(format!("RMCP OK, sequence number 0x{:02x}", seqnum)),
+        (0x06, _) => report.note(format!("RMCP reserved byte 0x{:02x} is not 0x00", reserved)),
+        _ => report.note(format!("RMCP version 0x{:02x} is not 0x06", version))
+    }
+
+    if class & 0b10000000 != 0 {
+        report.note(String::from("RMCP ACK"));
+        return report;
+    }
+
+    let payload = &bytes[4..];
+
+    match class & 0b00001111 {
+        MSG_CLASS_ASF => {
+            report.note(String::from("ASF class"));
+            match AsfMessage::from_bytes(payload, false) {
+                Ok(asf) => report.note(format!("ASF message type 0x{:02x} with {} data bytes",
+                    asf.msg_type, asf.data_len)),
+                Err(e) => return report.fail(format!("ASF message invalid: {:?}", e), e)
+            }
+        },
+        MSG_CLASS_IPMI => {
+            report.note(String::from("IPMI class"));
+            match payload.first() {
+                None => return report.fail(String::from("IPMI session header missing"),
+                    Error::PayloadTooSmall),
+                Some(0x06) => return report.fail(
+                    String::from("auth_type 0x06 indicates RMCP+ which is unsupported"),
+                    Error::UnsupportedProtocol),
+                Some(&IPMI_AUTH_TYPE_NONE) =>
+                    report.note(String::from("auth_type 0x00, no authentication")),
+                Some(&auth) if auth > 5 => 
+                    report.note(format!("auth_type 0x{:02x} is undefined", auth)),
+                Some(&auth) => report.note(format!("auth_type 0x{:02x}, with auth code", auth))
+            }
+
+            match Ipmi15Packet::from_bytes(payload, false) {
+                Ok(packet) => report.note(format!(
+                    "IPMI 1.5 session 0x{:08x} seq {}, netfn 0x{:02x} cmd 0x{:02x}",
+                    packet.session_id, packet.seqnum, packet.netfn(), packet.cmd())),
+                Err(e) => return report.fail(format!("IPMI 1.5 packet invalid: {:?}", e), e)
+            }
+        },
+        MSG_CLASS_OEM => {
+            if payload.len() < 4 {
+                return report.fail(String::from("OEM class, IANA truncated"),
+                    Error::PayloadTooSmall);
+            }
+            report.note(format!("OEM class, {} data bytes", payload.len() - 4));
+        },
+        other => report.note(format!("unknown class 0x{:02x}", other))
+    }
+
+    /* a frame that only decodes leniently is still worth pointing out */
+    if let Err(e) = crate::ipmi::rmcp::RmcpMessage::from_bytes(bytes, true) {
+        report.note(format!("rejected in strict mode: {:?}", e));
+    }
+
+    report
+}
+
+/* bytes of one labelled section per line, wrapped at 16 bytes */
+struct HexDump<'b> {
+    bytes: &'b [u8],
+    out: String
+}
+
+impl HexDump<'_> {
+    fn section(&mut self, label: &str, len: usize) {
+        let (head, rest) = self.bytes.split_at(len.min(self.bytes.len()));
+        self.bytes = rest;
+
+        if head.is_empty() {
+            return;
+        }
+
+        for (i, chunk) in head.chunks(16).enumerate() {
+            let hex = chunk.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ");
+            let label = if i == 0 { label } else { "" };
+            self.out.push_str(&format!("{:<16}{}\n", label, hex));
+        }
+    }
+}
+
+impl RmcpMessage<'_> {
+    /* 
+     * the serialized message as hex, one line per header or field group, e.g.
+     *   RMCP header     06 00 ff 07
+     */
+    pub fn hexdump(&self) -> String {
+        let bytes = match self.to_vec(false) {
+            Ok(bytes) => bytes,
+            Err(e) => return format!("unserializable: {:?}", e)
+        };
+
+        let mut dump = HexDump { bytes: &bytes, out: String::new() };
+        dump.section("RMCP header", 4);
+
+        match &self.data {
+            RmcpContent::Ack => {},
+            RmcpContent::Asf(asf) => {
+                dump.section("ASF header", asf.size() - asf.data.size());
+                dump.section("ASF data", asf.data.size());
+            },
+            RmcpContent::Ipmi15(packet) => {
+                dump.section("session header", 9);
+                dump.section("auth code", packet.auth_code.map_or(0, |code| code.len()));
+                dump.section("payload length", 1);
+                dump.section("IPMI header", 6);
+                let data = match packet.data.data {
+                    IpmiData::Request(data) => data,
+                    IpmiData::Response(_, data) => {
+                        dump.section("completion code", 1);
+                        data
+                    }
+                };
+                dump.section("IPMI data", data.len());
+                dump.section("checksum", 1);
+            },
+            RmcpContent::Oem { .. } => {
+                dump.section("OEM IANA", 4);
+                dump.section("OEM data", usize::MAX);
+            },
+            RmcpContent::Other(_) => dump.section("data", usize::MAX)
+        }
+
+        dump.out
+    }
+}
@@ -1,6 +1,8 @@
 use crate::ipmi::*;
+use crate::ipmi::util::parse_warn;
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IpmiMessage<'a> {
     pub peer_addr:  u8,
     pub netfn:      u8,
@@ -9,23 +11,28 @@ pub struct IpmiMessage<'a> {
     pub seqnum:     u8,
     pub local_lun:  u8,
     pub cmd:        u8,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub data:       IpmiData<'a>
 }
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ipmi15Packet<'a> {
     pub auth_type:  u8,
     pub seqnum:     u32,
     pub session_id: u32,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
     pub auth_code:  Option<&'a [u8]>,
     pub payload_len: u8,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub data:       IpmiMessage<'a>
 }
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IpmiData<'a> {
-    Request(&'a[u8]),
-    Response(u8, &'a[u8])
+    Request(#[cfg_attr(feature = "serde", serde(with = "serde_bytes"))] &'a[u8]),
+    Response(u8, #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))] &'a[u8])
 }
 
 pub const IPMI_PRIV_LEVEL_CALLBACK: u8 = 1;
@@ -34,12 +41,90 @@ pub const IPMI_PRIV_LEVEL_OPERATOR: u8 = 3;
 pub const IPMI_PRIV_LEVEL_ADMIN:    u8 = 4;
 pub const IPMI_PRIV_LEVEL_OEM:      u8 = 5;
 
+/* 
+ * Privilege level of a session. `Other` carries levels outside of 0-5, which
+ * are only accepted when not strict.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum PrivLevel {
+    /* only meaningful in Set Session Privilege Level, keep the present level */
+    Unchanged,
+    Callback,
+    User,
+    Operator,
+    Admin,
+    Oem,
+    Other(u8)
+}
+
+impl PrivLevel {
+    /* None if `level` is outside of the levels defined by the spec */
+    pub fn from_u8(level: u8) -> Option<PrivLevel> {
+        match level {
+            0 => Some(PrivLevel::Unchanged),
+            IPMI_PRIV_LEVEL_CALLBACK => Some(PrivLevel::Callback),
+            IPMI_PRIV_LEVEL_USER     => Some(PrivLevel::User),
+            IPMI_PRIV_LEVEL_OPERATOR => Some(PrivLevel::Operator),
+            IPMI_PRIV_LEVEL_ADMIN    => Some(PrivLevel::Admin),
+            IPMI_PRIV_LEVEL_OEM      => Some(PrivLevel::Oem),
+            _ => None
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            PrivLevel::Unchanged => 0,
+            PrivLevel::Callback  => IPMI_PRIV_LEVEL_CALLBACK,
+            PrivLevel::User      => IPMI_PRIV_LEVEL_USER,
+            PrivLevel::Operator  => IPMI_PRIV_LEVEL_OPERATOR,
+            PrivLevel::Admin     => IPMI_PRIV_LEVEL_ADMIN,
+            PrivLevel::Oem       => IPMI_PRIV_LEVEL_OEM,
+            PrivLevel::Other(level) => *level
+        }
+    }
+}
+
+impl BytesSerializationSized for PrivLevel {
+    fn size(&self) -> usize { 1 }
+}
+
+impl BytesSerializable for PrivLevel {
+    fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
+    {
+        if strict && PrivLevel::from_u8(self.as_u8()).is_none() {
+            return Err(Error::InvalidConfiguration);
+        }
+        self.as_u8().write_to_slice(slice, strict)
+    }
+}
+
+impl BytesDeserializable<'_> for PrivLevel {
+    fn from_bytes(slice: &'_ [u8], strict: bool) -> Result<PrivLevel, Error> {
+        let level = u8::from_bytes(slice, strict)?;
+        match PrivLevel::from_u8(level) {
+            Some(level) => Ok(level),
+            None if strict => Err(Error::InvalidConfiguration),
+            None => Ok(PrivLevel::Other(level))
+        }
+    }
+}
+
 pub const IPMI_AUTH_TYPE_NONE: u8 = 0;
 pub const IPMI_AUTH_TYPE_MD2:  u8 = 1;
 pub const IPMI_AUTH_TYPE_MD5:  u8 = 2;
 pub const IPMI_AUTH_TYPE_KEY:  u8 = 3;
 pub const IPMI_AUTH_TYPE_OEM:  u8 = 4;
 
+impl IpmiData<'_> {
+    /* the completion code of a response, None for requests */
+    pub fn completion_code(&self) -> Option<crate::ipmi::completion::CompletionCode> {
+        match self {
+            IpmiData::Request(_) => None,
+            IpmiData::Response(code, _) => Some((*code).into())
+        }
+    }
+}
+
 fn ipmi_cksum(slice: &[u8]) -> u8 {
     slice.iter().fold(0u8, |acc, n| acc.wrapping_add(*n)).wrapping_neg()
 }
@@ -49,6 +134,34 @@ fn ipmi_cksum_verify(slice: &[u8]) -> bool {
 }
 
 impl IpmiMessage<'_> {
+    /* advance the 6 bits message sequence number, wrapping 63 to 0 */
+    pub fn next_msg_seq(&mut self) -> u8 {
+        self.seqnum = self.seqnum.wrapping_add(1) & 0b00111111;
+        self.seqnum
+    }
+
+    /* 
+     * write_to_slice, then verify both checksums of what was written the
+     * same way from_bytes would
+     */
+    pub fn write_to_slice_checked(&self, slice: &mut [u8], strict: bool) -> Result<(), Error> {
+        self.write_to_slice(slice, strict)?;
+        IpmiMessage::verify_checksums(&slice[..self.size()])
+    }
+
+    /* check the header and the data checksums of a serialized message */
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn verify_checksums(bytes: &[u8]) -> Result<(), Error> {
+        let (fst, snd) = bytes.split_at_checked(3).ok_or(Error::PayloadTooSmall)?;
+
+        if !ipmi_cksum_verify(fst) || !ipmi_cksum_verify(snd) {
+            parse_warn!("IPMI message checksum mismatch over {=usize} bytes", bytes.len());
+            return Err(Error::InvalidChecksum);
+        }
+
+        Ok(())
+    }
+
     pub fn rs_addr(&self) -> u8 {
         if self.netfn % 2 == 0 {
             self.peer_addr
@@ -80,6 +193,22 @@ impl IpmiMessage<'_> {
             self.peer_lun
         }
     }
+    /* 
+     * build the response to this request, the requester and responder swap
+     * places on the wire while cmd and seqnum are kept
+     */
+    pub fn response_for<'b>(&self, completion_code: u8, data: &'b [u8]) -> IpmiMessage<'b> {
+        IpmiMessage {
+            peer_addr:  self.local_addr,
+            netfn:      self.netfn | 1,
+            peer_lun:   self.local_lun,
+            local_addr: self.peer_addr,
+            seqnum:     self.seqnum,
+            local_lun:  self.peer_lun,
+            cmd:        self.cmd,
+            data:       IpmiData::Response(completion_code, data)
+        }
+    }
 }
 
 impl BytesSerializationSized for Ipmi15Packet<'_> {
@@ -95,7 +224,7 @@ impl<'a> BytesSerializable for Ipmi15Packet<'a>
 {
     fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
     {
-        if self.size() < slice.len() {
+        if slice.len() < self.size() {
             return Err(Error::OutBufferTooSmall);
         }
 
@@ -104,7 +233,7 @@ impl<'a> BytesSerializable for Ipmi15Packet<'a>
         }
 
         if strict {
-            if self.data.size() > 255 {
+            if self.data.size() > IPMI_MAX_PAYLOAD {
                 return Err(Error::InvalidConfiguration);
             }
 
@@ -134,33 +263,109 @@ impl<'a> BytesSerializable for Ipmi15Packet<'a>
 
 impl<'a> Ipmi15Packet<'a>
 {
-    pub fn from_bytes(bytes: &'a [u8], strict: bool) -> Result<Ipmi15Packet, Error>
+    /* 
+     * a packet sent outside of a session, e.g. Get Channel Authentication
+     * Capabilities and Get Session Challenge
+     */
+    pub fn unauthenticated(data: IpmiMessage<'a>) -> Result<Ipmi15Packet<'a>, Error> {
+        if data.size() > IPMI_MAX_PAYLOAD {
+            return Err(Error::PayloadTooLarge);
+        }
+
+        Ok(Ipmi15Packet {
+            auth_type:   IPMI_AUTH_TYPE_NONE,
+            seqnum:      0,
+            session_id:  0,
+            auth_code:   None,
+            payload_len: data.size() as u8,
+            data
+        })
+    }
+
+    pub fn netfn(&self) -> u8 {
+        self.data.netfn
+    }
+
+    pub fn cmd(&self) -> u8 {
+        self.data.cmd
+    }
+
+    pub fn command_data(&self) -> &IpmiData<'a> {
+        &self.data.data
+    }
+
+    /* 
+     * advance the session sequence number, wrapping around 0 as a sequence
+     * number of 0 is only used outside of a session
+     */
+    pub fn next_seq(&mut self) -> u32 {
+        self.seqnum = match self.seqnum.wrapping_add(1) {
+            0 => 1,
+            n => n
+        };
+        self.seqnum
+    }
+}
+
+impl<'a> BytesDeserializable<'a> for Ipmi15Packet<'a>
+{
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn from_bytes(bytes: &'a [u8], strict: bool) -> Result<Ipmi15Packet<'a>, Error>
     {
         /* that is 10 bytes min for ipmi header + 7 bytes min for msg header */
         if bytes.len() < 17 { return Err(Error::PayloadTooSmall); }
 
+        let mut idx    = 0;
+        let auth_type  = crate::take_u8!(bytes, idx);
+
         /* \forall t \in ipmi 1.5 auth type, t \in [0, 5] */
-        if strict && bytes[0] > 5 {
-            return Err(Error::UndefinedAuthType(bytes[0]));
+        if strict && auth_type > 5 {
+            parse_warn!("undefined IPMI 1.5 auth type {=u8:#x}", auth_type);
+            return Err(Error::UndefinedAuthType(auth_type));
         }
 
-        let mut idx    = 0;
-        let auth_type  = crate::take_u8!(bytes, idx);
         let seqnum     = crate::take_le_u32!(bytes, idx);
         let session_id = crate::take_le_u32!(bytes, idx);
         let mut auth_code: Option<&'a [u8]> = None;
 
-        /* in case the packet contains auth code, we need 16 bytes more */
+        /* 
+         * in case the packet contains auth code, we need 16 bytes more, and
+         * the payload length byte following it
+         */
         if auth_type != IPMI_AUTH_TYPE_NONE {
-            if bytes.len() < 29 { return Err(Error::PayloadTooSmall); }
+            if bytes.len() < 29 {
+                parse_warn!("{=usize} bytes IPMI 1.5 packet truncated in the auth code", bytes.len());
+                return Err(Error::PayloadTooSmall);
+            }
             auth_code = Some(crate::take!(bytes, idx, 16))
         }
 
         let payload_len = crate::take_u8!(bytes, idx);
-        let data = IpmiMessage::from_bytes(crate::take_remain!(bytes, idx), strict)?;
+        let remain = crate::take_remain!(bytes, idx);
+
+        if strict && remain.len() < usize::from(payload_len) {
+            parse_warn!("IPMI 1.5 payload length {=u8} exceeds the {=usize} bytes left",
+                payload_len, remain.len());
+            return Err(Error::PayloadTooSmall);
+        }
+
+        /* 
+         * when not strict, the payload length is authoritative and anything
+         * past it is treated as padding, e.g. from switches padding datagrams
+         * to a minimum length
+         */
+        let payload = match remain.get(..usize::from(payload_len)) {
+            Some(payload) if !strict => payload,
+            _ => remain
+        };
+
+        let data = IpmiMessage::from_bytes(payload, strict)?;
 
         if data.size() != payload_len as usize {
-            return Err(Error::ExpectedSizeMismatch);
+            parse_warn!("IPMI 1.5 payload length {=u8} disagrees with {=usize} bytes message",
+                payload_len, data.size());
+            return Err(Error::ExpectedSizeMismatch {
+                expected: usize::from(payload_len), actual: data.size() });
         }
 
         Ok(Ipmi15Packet {
@@ -175,7 +380,7 @@ impl<'a> Ipmi15Packet<'a>
     }
 }
 
-impl<'a> BytesSerializationSized for IpmiMessage<'_> {
+impl BytesSerializationSized for IpmiMessage<'_> {
     fn size(&self) -> usize {
         match self.data {
             IpmiData::Request(dat) => dat.len() + 7,
@@ -188,12 +393,20 @@ impl<'a> BytesSerializable for IpmiMessage<'a>
 {
     fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
     {
-        if strict {
-            if self.peer_lun > 0b00000011 || self.local_lun > 0b00000011 
-                || self.seqnum > 0b11111100
-            {
-                return Err(Error::InvalidConfiguration)
-            }
+        if slice.len() < self.size() {
+            return Err(Error::OutBufferTooSmall);
+        }
+
+        if strict && (self.peer_lun > 0b00000011 || self.local_lun > 0b00000011 
+                || self.seqnum > 0b00111111)
+        {
+            return Err(Error::InvalidConfiguration)
+        }
+
+        /* even netfn are requests, odd netfn are responses */
+        let is_request = matches!(self.data, IpmiData::Request(_));
+        if strict && (self.netfn % 2 == 0) != is_request {
+            return Err(Error::InvalidConfiguration)
         }
 
         slice[0] = self.peer_addr;
@@ -225,24 +438,23 @@ impl<'a> BytesSerializable for IpmiMessage<'a>
 
 impl<'a> BytesDeserializable<'a> for IpmiMessage<'a>
 {
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
     fn from_bytes(bytes: &'a [u8], _strict: bool) -> Result<IpmiMessage<'a>, Error> 
     {
         if bytes.len() < 7 {
             return Err(Error::PayloadTooSmall);
         }
 
-        let (fst, snd) = bytes.split_at(3);
+        IpmiMessage::verify_checksums(bytes)?;
 
-        if !ipmi_cksum_verify(fst) || !ipmi_cksum_verify(snd) {
-            return Err(Error::InvalidChecksum);
-        }
-
-        let peer_addr = fst[0];
-        let netfn_lun = fst[1];
+        let mut idx    = 0;
+        let peer_addr  = crate::take_u8!(bytes, idx);
+        let netfn_lun  = crate::take_u8!(bytes, idx);
+        let _          = crate::take_u8!(bytes, idx);
 
-        let local_addr = snd[0];
-        let seqnum_lun = snd[1];
-        let cmd        = snd[2];
+        let local_addr = crate::take_u8!(bytes, idx);
+        let seqnum_lun = crate::take_u8!(bytes, idx);
+        let cmd        = crate::take_u8!(bytes, idx);
 
         let netfn      = netfn_lun >> 2;
         let peer_lun   = netfn_lun & 0b00000011;
@@ -250,18 +462,74 @@ impl<'a> BytesDeserializable<'a> for IpmiMessage<'a>
         let seqnum = seqnum_lun >> 2;
         let local_lun = seqnum_lun & 0b00000011;
 
-        /* remove the checksum byte, this can never fail as we checked 
-         * payload length earlier 
-         */
-        let (_, dat)   = bytes[6..].split_last().unwrap();
+        /* remove the checksum byte */
+        let (_, dat)   = crate::take_remain!(bytes, idx).split_last()
+            .ok_or(Error::PayloadTooSmall)?;
 
-        let data = if netfn % 2 == 0 {
-                IpmiData::Request(dat)
-            } else {
-                IpmiData::Response(dat[0], &dat[1..])
-            };
+        /* 
+         * a response has at least the completion code, which can be the only 
+         * byte for commands without response data
+         */
+        let data = match dat.split_first() {
+            _ if netfn % 2 == 0 => IpmiData::Request(dat),
+            Some((code, rest)) => IpmiData::Response(*code, rest),
+            None => return Err(Error::PayloadTooSmall)
+        };
 
         Ok(IpmiMessage { peer_addr, netfn, local_addr, local_lun, seqnum, 
             peer_lun, cmd, data })
     }
 }
+
+#[cfg(feature = "alloc")]
+impl Ipmi15Packet<'_> {
+    pub fn to_vec(&self, strict: bool) -> Result<alloc::vec::Vec<u8>, Error> {
+        crate::ipmi::to_vec(self, strict)
+    }
+}
+
+/* 
+ * Messages that serialize in strict mode: LUNs fit in 2 bits, seqnum in 6
+ * bits, the data variant agrees with the netfn parity and the message fits
+ * in the one byte payload length of an IPMI 1.5 session header.
+ */
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for IpmiMessage<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let netfn = u.int_in_range(0..=0b00111111)?;
+        let len   = u.int_in_range(0..=(IPMI_MAX_PAYLOAD - 8))?;
+
+        Ok(IpmiMessage {
+            peer_addr:  u.arbitrary()?,
+            netfn,
+            peer_lun:   u.int_in_range(0..=0b00000011)?,
+            local_addr: u.arbitrary()?,
+            seqnum:     u.int_in_range(0..=0b00111111)?,
+            local_lun:  u.int_in_range(0..=0b00000011)?,
+            cmd:        u.arbitrary()?,
+            data: if netfn % 2 == 0 {
+                IpmiData::Request(u.bytes(len)?)
+            } else {
+                IpmiData::Response(u.arbitrary()?, u.bytes(len)?)
+            }
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Ipmi15Packet<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let auth_type = u.int_in_range(IPMI_AUTH_TYPE_NONE..=IPMI_AUTH_TYPE_OEM)?;
+        let auth_code = if auth_type == IPMI_AUTH_TYPE_NONE { None } else { Some(u.bytes(16)?) };
+        let data: IpmiMessage = u.arbitrary()?;
+
+        Ok(Ipmi15Packet {
+            auth_type,
+            seqnum:      u.arbitrary()?,
+            session_id:  u.arbitrary()?,
+            auth_code,
+            payload_len: data.size() as u8,
+            data
+        })
+    }
+}
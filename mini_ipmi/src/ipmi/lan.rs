@@ -0,0 +1,90 @@
+use crate::ipmi::{BytesDeserializable, CallError, Error};
+use crate::ipmi::cmd::*;
+
+/* 
+ * Queries the network configuration of a BMC channel with Get LAN
+ * Configuration Parameters.
+ *
+ * `transport` performs the round trip, it writes the response data following
+ * the completion code into the buffer and returns the completion code with
+ * the number of bytes written.
+ */
+pub struct LanConfigClient<F> {
+    pub channel: u8,
+    pub transport: F
+}
+
+impl<F> LanConfigClient<F> {
+    pub fn new(channel: u8, transport: F) -> LanConfigClient<F> {
+        LanConfigClient { channel, transport }
+    }
+
+    /* copy the data of parameter `selector` into `out`, return its length */
+    pub fn get_param<E>(&mut self, selector: u8, out: &mut [u8]) 
+        -> Result<usize, CallError<E>>
+        where F: FnMut(&GetLanConfigParamRequest, &mut [u8]) -> Result<(u8, usize), E>
+    {
+        let mut scratch = [0u8; 256];
+        let req = GetLanConfigParamRequest {
+            channel: self.channel,
+            parameter_selector: selector,
+            set_selector: 0,
+            block_selector: 0
+        };
+
+        let (code, len) = (self.transport)(&req, &mut scratch).map_err(CallError::Transport)?;
+
+        if code != 0 {
+            return Err(CallError::CompletionCode(code));
+        }
+
+        let res = GetLanConfigParamResponse::from_bytes(&scratch[..len.min(scratch.len())], true)
+            .map_err(CallError::Parse)?;
+
+        if out.len() < res.data.len() {
+            return Err(CallError::Parse(Error::OutBufferTooSmall));
+        }
+
+        out[..res.data.len()].copy_from_slice(res.data);
+        Ok(res.data.len())
+    }
+
+    fn get_fixed<E, const N: usize>(&mut self, selector: u8) -> Result<[u8; N], CallError<E>>
+        where F: FnMut(&GetLanConfigParamRequest, &mut [u8]) -> Result<(u8, usize), E>
+    {
+        let mut buf = [0u8; 256];
+        let len = self.get_param(selector, &mut buf)?;
+
+        if len < N {
+            return Err(CallError::Parse(Error::PayloadTooSmall));
+        }
+
+        let mut value = [0u8; N];
+        value.copy_from_slice(&buf[..N]);
+        Ok(value)
+    }
+
+    pub fn lan_ip<E>(&mut self) -> Result<[u8; 4], CallError<E>>
+        where F: FnMut(&GetLanConfigParamRequest, &mut [u8]) -> Result<(u8, usize), E>
+    {
+        self.get_fixed(LAN_PARAM_IP_ADDRESS)
+    }
+
+    pub fn lan_subnet<E>(&mut self) -> Result<[u8; 4], CallError<E>>
+        where F: FnMut(&GetLanConfigParamRequest, &mut [u8]) -> Result<(u8, usize), E>
+    {
+        self.get_fixed(LAN_PARAM_SUBNET_MASK)
+    }
+
+    pub fn lan_gateway<E>(&mut self) -> Result<[u8; 4], CallError<E>>
+        where F: FnMut(&GetLanConfigParamRequest, &mut [u8]) -> Result<(u8, usize), E>
+    {
+        self.get_fixed(LAN_PARAM_DEFAULT_GATEWAY)
+    }
+
+    pub fn lan_mac<E>(&mut self) -> Result<[u8; 6], CallError<E>>
+        where F: FnMut(&GetLanConfigParamRequest, &mut [u8]) -> Result<(u8, usize), E>
+    {
+        self.get_fixed(LAN_PARAM_MAC_ADDRESS)
+    }
+}
@@ -1,10 +1,40 @@
 pub mod rmcp;
 pub mod asf;
+pub mod rsp;
+#[allow(clippy::module_inception)]
 pub mod ipmi;
 mod util;
 pub mod cmd;
+pub mod completion;
+pub mod sdr;
+pub mod sel;
+pub mod session;
+pub mod time;
+pub mod lan;
+pub mod pet;
+pub mod sol;
+pub mod serial;
+pub mod oem;
+pub mod rmcpp;
+#[cfg(feature = "hmac")]
+pub mod auth;
+#[cfg(feature = "aes")]
+pub mod crypto;
+#[cfg(feature = "alloc")]
+pub mod diag;
+#[cfg(feature = "alloc")]
+pub mod owned;
+#[cfg(feature = "client")]
+pub mod client;
+
+/* 
+ * Largest payload behind a one byte length field, the IPMI 1.5 session
+ * payload length and the ASF data length
+ */
+pub const IPMI_MAX_PAYLOAD: usize = 255;
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
     OutBufferTooSmall,
     InvalidConfiguration,
@@ -13,28 +43,120 @@ pub enum Error {
     InvalidRmcpVersionNumber(u8),
     InvalidRmcpReservedByte(u8),
     UnsupportedProtocol,
-    ExpectedSizeMismatch,
+    /* a length field disagrees with the actual length */
+    ExpectedSizeMismatch { expected: usize, actual: usize },
     InvalidChecksum,
     UndefinedAuthType(u8)
 }
 
+impl Error {
+    /* 
+     * Stable one byte code of the variant, for forwarding errors over a
+     * narrow channel. The values carried by variants are not encoded.
+     */
+    pub fn as_code(&self) -> u8 {
+        match self {
+            Error::OutBufferTooSmall             => 0x01,
+            Error::InvalidConfiguration          => 0x02,
+            Error::PayloadTooLarge               => 0x03,
+            Error::PayloadTooSmall               => 0x04,
+            Error::InvalidRmcpVersionNumber(_)   => 0x05,
+            Error::InvalidRmcpReservedByte(_)    => 0x06,
+            Error::UnsupportedProtocol           => 0x07,
+            Error::ExpectedSizeMismatch { .. }   => 0x08,
+            Error::InvalidChecksum               => 0x09,
+            Error::UndefinedAuthType(_)          => 0x0a
+        }
+    }
+
+    /* the inverse of `as_code`, carried values come back as zeros */
+    pub fn from_code(code: u8) -> Option<Error> {
+        match code {
+            0x01 => Some(Error::OutBufferTooSmall),
+            0x02 => Some(Error::InvalidConfiguration),
+            0x03 => Some(Error::PayloadTooLarge),
+            0x04 => Some(Error::PayloadTooSmall),
+            0x05 => Some(Error::InvalidRmcpVersionNumber(0)),
+            0x06 => Some(Error::InvalidRmcpReservedByte(0)),
+            0x07 => Some(Error::UnsupportedProtocol),
+            0x08 => Some(Error::ExpectedSizeMismatch { expected: 0, actual: 0 }),
+            0x09 => Some(Error::InvalidChecksum),
+            0x0a => Some(Error::UndefinedAuthType(0)),
+            _ => None
+        }
+    }
+}
+
+/* 
+ * Failure of a helper driving a command round trip through a caller supplied
+ * transport, `E` is the error type of the transport.
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub enum CallError<E> {
+    Transport(E),
+    Parse(Error),
+    CompletionCode(u8)
+}
+
 pub fn summon_from_bytes<'a, T: BytesDeserializable<'a>>(slice: &'a [u8], strict: bool) -> Result<T, Error>
 {
     T::from_bytes(slice, strict)
 }
 
+/* serialize `value` into a stack array, returning it with the length used */
+pub fn serialize_to_array<T: BytesSerializable, const N: usize>(value: &T, strict: bool)
+    -> Result<([u8; N], usize), Error>
+{
+    let mut buf = [0u8; N];
+    value.write_to_slice(&mut buf, strict)?;
+    Ok((buf, value.size()))
+}
+
+/* allocate exactly `size()` bytes and serialize `value` into it */
+#[cfg(feature = "alloc")]
+pub fn to_vec<T: BytesSerializable>(value: &T, strict: bool) -> Result<alloc::vec::Vec<u8>, Error>
+{
+    let mut buf = alloc::vec![0u8; value.size()];
+    value.write_to_slice(&mut buf, strict)?;
+    Ok(buf)
+}
+
 pub trait BytesSerializationSized {
     fn size(&self) -> usize;
 }
 
 pub trait BytesSerializable: core::marker::Sized + BytesSerializationSized {
     fn write_to_slice(&self, _: &mut [u8], strict: bool) -> Result<(), Error>;
+
+    /* 
+     * serialize to the front of `slice` and split it into the (written, remaining)
+     * parts, so multiple frames can be chained into one buffer
+     */
+    fn write_and_split<'b>(&self, slice: &'b mut [u8], strict: bool)
+        -> Result<(&'b mut [u8], &'b mut [u8]), Error>
+    {
+        self.write_to_slice(slice, strict)?;
+        Ok(slice.split_at_mut(self.size()))
+    }
 }
 
 pub trait BytesDeserializable<'a>: core::marker::Sized + BytesSerializationSized {
     fn from_bytes(slice: &'a [u8], strict: bool) -> Result<Self, Error>;
 }
 
+/* spell out the strictness at call sites instead of passing a bare bool */
+pub trait BytesDeserializableExt<'a>: BytesDeserializable<'a> {
+    fn from_bytes_strict(slice: &'a [u8]) -> Result<Self, Error> {
+        Self::from_bytes(slice, true)
+    }
+
+    fn from_bytes_lenient(slice: &'a [u8]) -> Result<Self, Error> {
+        Self::from_bytes(slice, false)
+    }
+}
+
+impl<'a, T: BytesDeserializable<'a>> BytesDeserializableExt<'a> for T {}
+
 impl<const N: usize> BytesSerializationSized for [u8; N] {
     fn size(&self) -> usize { 
         N
@@ -65,13 +187,38 @@ impl<const N: usize> BytesDeserializable<'_> for [u8; N] {
     }
 }
 
+/* a byte slice field always consumes the remaining bytes of the payload */
+impl BytesSerializationSized for &'_ [u8] {
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl BytesSerializable for &'_ [u8] {
+    fn write_to_slice(&self, slice: &mut[u8], _strict: bool) -> Result<(), Error>
+    {
+        if slice.len() < self.size() {
+            return Err(Error::OutBufferTooSmall);
+        }
+
+        slice[..self.len()].copy_from_slice(self);
+        Ok(())
+    }
+}
+
+impl<'a> BytesDeserializable<'a> for &'a [u8] {
+    fn from_bytes(slice: &'a [u8], _strict: bool) -> Result<&'a [u8], Error> {
+        Ok(slice)
+    }
+}
+
 impl BytesSerializationSized for u32 {
     fn size(&self) -> usize { 4 }
 }
 
 impl BytesDeserializable<'_> for u8 {
     fn from_bytes(slice: &'_ [u8], _strict: bool) -> Result<u8, Error> {
-        if slice.len() < 1 { return Err(Error::PayloadTooSmall) }
+        if slice.is_empty() { return Err(Error::PayloadTooSmall) }
         Ok(slice[0])
     }
 }
@@ -90,3 +237,21 @@ impl BytesSerializable for u8 {
         Ok(())
     }
 }
+
+/* signed bytes, e.g. sensor offsets, are the two's complement byte */
+impl BytesSerializationSized for i8 {
+    fn size(&self) -> usize { 1 }
+}
+
+impl BytesSerializable for i8 {
+    fn write_to_slice(&self, slice: &mut[u8], strict: bool) -> Result<(), Error>
+    {
+        (*self as u8).write_to_slice(slice, strict)
+    }
+}
+
+impl BytesDeserializable<'_> for i8 {
+    fn from_bytes(slice: &'_ [u8], strict: bool) -> Result<i8, Error> {
+        u8::from_bytes(slice, strict).map(|byte| byte as i8)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::ipmi::Error;
+use crate::ipmi::rmcp::RmcpMessage;
+
+/* IANA enterprise numbers of some BMC vendors */
+pub const IANA_IBM:        u32 = 2;
+pub const IANA_HP:         u32 = 11;
+pub const IANA_SUN:        u32 = 42;
+pub const IANA_INTEL:      u32 = 343;
+pub const IANA_DELL:       u32 = 674;
+pub const IANA_ASF:        u32 = crate::ipmi::asf::ASF_IANA;
+pub const IANA_SUPERMICRO: u32 = 10876;
+pub const IANA_LENOVO:     u32 = 19046;
+
+/* 
+ * IPMI packs manufacturer ids in 3 bytes, least significant byte first, 
+ * e.g. in Get Channel Authentication Capabilities and Get Device ID
+ */
+pub fn iana_from_3(bytes: [u8; 3]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+/* the inverse of `iana_from_3`, the most significant byte is dropped */
+pub fn iana_to_3(iana: u32) -> [u8; 3] {
+    let [b0, b1, b2, _] = iana.to_le_bytes();
+    [b0, b1, b2]
+}
+
+/* vendor parser of the data following the IANA of an OEM RMCP message */
+pub type OemDecoder<'a, T> = fn(&'a [u8], bool) -> Result<T, Error>;
+
+/* result of `RmcpMessage::from_bytes_with_oem` */
+#[derive(PartialEq, Eq, Debug)]
+pub enum OemDecoded<'a, T> {
+    /* OEM message of a registered IANA, `message` still holds the raw data */
+    Decoded { message: RmcpMessage<'a>, value: T },
+    Plain(RmcpMessage<'a>)
+}
+
+impl<'a, T> OemDecoded<'a, T> {
+    pub fn message(&self) -> &RmcpMessage<'a> {
+        match self {
+            OemDecoded::Decoded { message, .. } => message,
+            OemDecoded::Plain(message) => message
+        }
+    }
+}
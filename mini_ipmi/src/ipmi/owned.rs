@@ -0,0 +1,261 @@
+use alloc::vec::Vec;
+
+use crate::ipmi::asf::{AsfData, AsfMessage};
+use crate::ipmi::ipmi::{Ipmi15Packet, IpmiData, IpmiMessage};
+use crate::ipmi::rmcp::{RmcpContent, RmcpMessage};
+use crate::ipmi::rsp::RspMessage;
+
+/*
+ * Owned counterparts of the message types, for keeping a decoded message
+ * past the lifetime of the buffer it was decoded from. Every borrowed slice
+ * is copied into a Vec.
+ */
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RmcpMessageOwned {
+    pub version: u8,
+    pub reserved: u8,
+    pub sequence_number: u8,
+    pub message_class: u8,
+    pub data: RmcpContentOwned
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum RmcpContentOwned {
+    Ack,
+    Asf(AsfMessageOwned),
+    Ipmi15(Ipmi15PacketOwned),
+    Oem { iana: u32, data: Vec<u8> },
+    Other(Vec<u8>)
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct AsfMessageOwned {
+    pub iana: u32,
+    pub msg_type: u8,
+    pub msg_tag: u8,
+    pub data_len: u8,
+    pub data: AsfDataOwned
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum AsfDataOwned {
+    Other(Vec<u8>),
+    Ping,
+    Pong { iana: u32
+         , oem_defined: u32
+         , entities: u8
+         , interactions: u8
+         , oem_data: Vec<u8>
+         }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Ipmi15PacketOwned {
+    pub auth_type: u8,
+    pub seqnum: u32,
+    pub session_id: u32,
+    pub auth_code: Option<Vec<u8>>,
+    pub payload_len: u8,
+    pub data: IpmiMessageOwned
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct IpmiMessageOwned {
+    pub peer_addr: u8,
+    pub netfn: u8,
+    pub peer_lun: u8,
+    pub local_addr: u8,
+    pub seqnum: u8,
+    pub local_lun: u8,
+    pub cmd: u8,
+    pub data: IpmiDataOwned
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum IpmiDataOwned {
+    Request(Vec<u8>),
+    Response(u8, Vec<u8>)
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RspMessageOwned {
+    pub session_id: u32,
+    pub seqnum: u32,
+    pub payload: Vec<u8>,
+    pub pad_len: u8,
+    pub next_header: u8,
+    pub integrity: Vec<u8>
+}
+
+impl RmcpMessage<'_> {
+    pub fn to_owned(&self) -> RmcpMessageOwned {
+        RmcpMessageOwned {
+            version: self.version,
+            reserved: self.reserved,
+            sequence_number: self.sequence_number,
+            message_class: self.message_class,
+            data: match &self.data {
+                RmcpContent::Ack             => RmcpContentOwned::Ack,
+                RmcpContent::Asf(asf)        => RmcpContentOwned::Asf(asf.to_owned()),
+                RmcpContent::Ipmi15(packet)  => RmcpContentOwned::Ipmi15(packet.to_owned()),
+                RmcpContent::Oem { iana, data } =>
+                    RmcpContentOwned::Oem { iana: *iana, data: data.to_vec() },
+                RmcpContent::Other(bytes)    => RmcpContentOwned::Other(bytes.to_vec())
+            }
+        }
+    }
+}
+
+impl RmcpMessageOwned {
+    pub fn as_borrowed(&self) -> RmcpMessage<'_> {
+        RmcpMessage {
+            version: self.version,
+            reserved: self.reserved,
+            sequence_number: self.sequence_number,
+            message_class: self.message_class,
+            data: match &self.data {
+                RmcpContentOwned::Ack            => RmcpContent::Ack,
+                RmcpContentOwned::Asf(asf)       => RmcpContent::Asf(asf.as_borrowed()),
+                RmcpContentOwned::Ipmi15(packet) => RmcpContent::Ipmi15(packet.as_borrowed()),
+                RmcpContentOwned::Oem { iana, data } =>
+                    RmcpContent::Oem { iana: *iana, data },
+                RmcpContentOwned::Other(bytes)   => RmcpContent::Other(bytes)
+            }
+        }
+    }
+}
+
+impl AsfMessage<'_> {
+    pub fn to_owned(&self) -> AsfMessageOwned {
+        AsfMessageOwned {
+            iana: self.iana,
+            msg_type: self.msg_type,
+            msg_tag: self.msg_tag,
+            data_len: self.data_len,
+            data: match &self.data {
+                AsfData::Other(bytes) => AsfDataOwned::Other(bytes.to_vec()),
+                AsfData::Ping => AsfDataOwned::Ping,
+                AsfData::Pong { iana, oem_defined, entities, interactions, oem_data } =>
+                    AsfDataOwned::Pong {
+                        iana: *iana,
+                        oem_defined: *oem_defined,
+                        entities: *entities,
+                        interactions: *interactions,
+                        oem_data: oem_data.to_vec()
+                    }
+            }
+        }
+    }
+}
+
+impl AsfMessageOwned {
+    pub fn as_borrowed(&self) -> AsfMessage<'_> {
+        AsfMessage {
+            iana: self.iana,
+            msg_type: self.msg_type,
+            msg_tag: self.msg_tag,
+            data_len: self.data_len,
+            data: match &self.data {
+                AsfDataOwned::Other(bytes) => AsfData::Other(bytes),
+                AsfDataOwned::Ping => AsfData::Ping,
+                AsfDataOwned::Pong { iana, oem_defined, entities, interactions, oem_data } =>
+                    AsfData::Pong {
+                        iana: *iana,
+                        oem_defined: *oem_defined,
+                        entities: *entities,
+                        interactions: *interactions,
+                        oem_data
+                    }
+            }
+        }
+    }
+}
+
+impl Ipmi15Packet<'_> {
+    pub fn to_owned(&self) -> Ipmi15PacketOwned {
+        Ipmi15PacketOwned {
+            auth_type: self.auth_type,
+            seqnum: self.seqnum,
+            session_id: self.session_id,
+            auth_code: self.auth_code.map(|code| code.to_vec()),
+            payload_len: self.payload_len,
+            data: self.data.to_owned()
+        }
+    }
+}
+
+impl Ipmi15PacketOwned {
+    pub fn as_borrowed(&self) -> Ipmi15Packet<'_> {
+        Ipmi15Packet {
+            auth_type: self.auth_type,
+            seqnum: self.seqnum,
+            session_id: self.session_id,
+            auth_code: self.auth_code.as_deref(),
+            payload_len: self.payload_len,
+            data: self.data.as_borrowed()
+        }
+    }
+}
+
+impl IpmiMessage<'_> {
+    pub fn to_owned(&self) -> IpmiMessageOwned {
+        IpmiMessageOwned {
+            peer_addr: self.peer_addr,
+            netfn: self.netfn,
+            peer_lun: self.peer_lun,
+            local_addr: self.local_addr,
+            seqnum: self.seqnum,
+            local_lun: self.local_lun,
+            cmd: self.cmd,
+            data: match self.data {
+                IpmiData::Request(bytes) => IpmiDataOwned::Request(bytes.to_vec()),
+                IpmiData::Response(code, bytes) => IpmiDataOwned::Response(code, bytes.to_vec())
+            }
+        }
+    }
+}
+
+impl IpmiMessageOwned {
+    pub fn as_borrowed(&self) -> IpmiMessage<'_> {
+        IpmiMessage {
+            peer_addr: self.peer_addr,
+            netfn: self.netfn,
+            peer_lun: self.peer_lun,
+            local_addr: self.local_addr,
+            seqnum: self.seqnum,
+            local_lun: self.local_lun,
+            cmd: self.cmd,
+            data: match &self.data {
+                IpmiDataOwned::Request(bytes) => IpmiData::Request(bytes),
+                IpmiDataOwned::Response(code, bytes) => IpmiData::Response(*code, bytes)
+            }
+        }
+    }
+}
+
+impl RspMessage<'_> {
+    pub fn to_owned(&self) -> RspMessageOwned {
+        RspMessageOwned {
+            session_id: self.session_id,
+            seqnum: self.seqnum,
+            payload: self.payload.to_vec(),
+            pad_len: self.pad_len,
+            next_header: self.next_header,
+            integrity: self.integrity.to_vec()
+        }
+    }
+}
+
+impl RspMessageOwned {
+    pub fn as_borrowed(&self) -> RspMessage<'_> {
+        RspMessage {
+            session_id: self.session_id,
+            seqnum: self.seqnum,
+            payload: &self.payload,
+            pad_len: self.pad_len,
+            next_header: self.next_header,
+            integrity: &self.integrity
+        }
+    }
+}
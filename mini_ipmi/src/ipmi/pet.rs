@@ -0,0 +1,136 @@
+use crate::{take_array, take_be_u16, take_be_u32, take_u8, take_remain};
+use crate::ipmi::*;
+
+/* size of the fixed part of the PET variable bindings */
+pub const PET_FIXED_SIZE: usize = 46;
+/* a PET without OEM custom fields still carries the 0xc1 end marker */
+pub const PET_MIN_SIZE: usize = PET_FIXED_SIZE + 1;
+pub const PET_OEM_END_OF_FIELDS: u8 = 0xc1;
+
+/* Platform Event Trap variable bindings sent by the BMC as an SNMP trap */
+#[derive(PartialEq, Eq, Debug)]
+pub struct PetTrap<'a> {
+    pub guid: [u8; 16],
+    pub seqnum: u16,
+    pub timestamp: u32,
+    pub utc_offset: u16,
+    pub trap_source_type: u8,
+    pub event_source_type: u8,
+    pub event_severity: u8,
+    pub sensor_device: u8,
+    pub sensor_number: u8,
+    pub entity: u8,
+    pub entity_instance: u8,
+    pub event_data: [u8; 8],
+    pub language_code: u8,
+    pub manufacturer_id: u32,
+    pub system_id: u16,
+    /* OEM custom fields, including the 0xc1 end marker */
+    pub oem_custom: &'a [u8]
+}
+
+impl PetTrap<'_> {
+    /* UTC offset in minutes, 0xffff when unspecified */
+    pub fn utc_offset_minutes(&self) -> Option<i16> {
+        match self.utc_offset {
+            0xffff => None,
+            offset => Some(offset as i16)
+        }
+    }
+}
+
+impl BytesSerializationSized for PetTrap<'_> {
+    fn size(&self) -> usize {
+        PET_FIXED_SIZE + self.oem_custom.len()
+    }
+}
+
+impl BytesSerializable for PetTrap<'_> {
+    fn write_to_slice(&self, bytes: &mut [u8], strict: bool) -> Result<(), Error>
+    {
+        if bytes.len() < self.size() {
+            return Err(Error::OutBufferTooSmall);
+        }
+
+        if strict && self.oem_custom.last() != Some(&PET_OEM_END_OF_FIELDS) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        bytes[0..16].copy_from_slice(&self.guid);
+        bytes[16..18].copy_from_slice(&self.seqnum.to_be_bytes());
+        bytes[18..22].copy_from_slice(&self.timestamp.to_be_bytes());
+        bytes[22..24].copy_from_slice(&self.utc_offset.to_be_bytes());
+        bytes[24] = self.trap_source_type;
+        bytes[25] = self.event_source_type;
+        bytes[26] = self.event_severity;
+        bytes[27] = self.sensor_device;
+        bytes[28] = self.sensor_number;
+        bytes[29] = self.entity;
+        bytes[30] = self.entity_instance;
+        bytes[31..39].copy_from_slice(&self.event_data);
+        bytes[39] = self.language_code;
+        bytes[40..44].copy_from_slice(&self.manufacturer_id.to_be_bytes());
+        bytes[44..46].copy_from_slice(&self.system_id.to_be_bytes());
+        bytes[46..][..self.oem_custom.len()].copy_from_slice(self.oem_custom);
+        Ok(())
+    }
+}
+
+impl<'a> BytesDeserializable<'a> for PetTrap<'a> {
+    fn from_bytes(bytes: &'a [u8], strict: bool) -> Result<PetTrap<'a>, Error>
+    {
+        /* 
+         * +----Field-----------(size)-+
+         * | GUID                 (16) |
+         * | Sequence Number       (2) |
+         * | Local Timestamp       (4) |
+         * | UTC Offset            (2) |
+         * | Trap Source Type      (1) |
+         * | Event Source Type     (1) |
+         * | Event Severity        (1) |
+         * | Sensor Device         (1) |
+         * | Sensor Number         (1) |
+         * | Entity                (1) |
+         * | Entity Instance       (1) |
+         * | Event Data            (8) |
+         * | Language Code         (1) |
+         * | Manufacturer ID       (4) |
+         * | System ID             (2) |
+         * | OEM Custom Fields   (var) |
+         * +---------------------------+
+         */
+        let min = if strict { PET_MIN_SIZE } else { PET_FIXED_SIZE };
+        if bytes.len() < min {
+            return Err(Error::PayloadTooSmall);
+        }
+
+        let mut idx = 0;
+        let guid              = take_array!(bytes, idx, 16);
+        let seqnum            = take_be_u16!(bytes, idx);
+        let timestamp         = take_be_u32!(bytes, idx);
+        let utc_offset        = take_be_u16!(bytes, idx);
+        let trap_source_type  = take_u8!(bytes, idx);
+        let event_source_type = take_u8!(bytes, idx);
+        let event_severity    = take_u8!(bytes, idx);
+        let sensor_device     = take_u8!(bytes, idx);
+        let sensor_number     = take_u8!(bytes, idx);
+        let entity            = take_u8!(bytes, idx);
+        let entity_instance   = take_u8!(bytes, idx);
+        let event_data        = take_array!(bytes, idx, 8);
+        let language_code     = take_u8!(bytes, idx);
+        let manufacturer_id   = take_be_u32!(bytes, idx);
+        let system_id         = take_be_u16!(bytes, idx);
+        let oem_custom        = take_remain!(bytes, idx);
+
+        if strict && oem_custom.last() != Some(&PET_OEM_END_OF_FIELDS) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        Ok(PetTrap {
+            guid, seqnum, timestamp, utc_offset, trap_source_type,
+            event_source_type, event_severity, sensor_device, sensor_number,
+            entity, entity_instance, event_data, language_code,
+            manufacturer_id, system_id, oem_custom
+        })
+    }
+}
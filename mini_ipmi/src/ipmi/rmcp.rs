@@ -1,27 +1,55 @@
-use crate::ipmi::asf::AsfMessage;
-use crate::ipmi::ipmi::Ipmi15Packet;
+use crate::ipmi::asf::{AsfData, AsfMessage, ASF_MSG_TYPE_PING, ASF_MSG_TYPE_PONG};
+use crate::ipmi::ipmi::{Ipmi15Packet, IpmiMessage, IPMI_AUTH_TYPE_NONE};
+use crate::ipmi::oem::{OemDecoded, OemDecoder};
 use crate::ipmi::*;
+use crate::ipmi::util::parse_warn;
 
 pub const MSG_CLASS_ASF:  u8 = 0b00000110;
 pub const MSG_CLASS_IPMI: u8 = 0b00000111;
 pub const MSG_CLASS_OEM:  u8 = 0b00001000;
 
+/* set on the message class of an ACK */
+pub const MSG_CLASS_ACK:  u8 = 0b10000000;
+
+/* sequence number 255 tells the receiver not to send an RMCP ACK */
+pub const RMCP_SEQ_NO_ACK: u8 = 0xff;
+
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RmcpMessage<'a> {
     pub version: u8,  /* must be 0x06 to be compatible wth standard */
     pub reserved: u8, /* must be 0x00 to be compatible with standard */
     pub sequence_number: u8,
+    /* for an ACK, the acknowledged class with MSG_CLASS_ACK set */
     pub message_class:   u8,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub data: RmcpContent<'a>
 }
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RmcpContent<'a> {
     Ack,
-    Asf(crate::ipmi::asf::AsfMessage<'a>),
-    Ipmi15(crate::ipmi::ipmi::Ipmi15Packet<'a>),
-    Oem { iana: u32, data: &'a [u8] },
-    Other(&'a [u8])
+    Asf(#[cfg_attr(feature = "serde", serde(borrow))] crate::ipmi::asf::AsfMessage<'a>),
+    Ipmi15(#[cfg_attr(feature = "serde", serde(borrow))] crate::ipmi::ipmi::Ipmi15Packet<'a>),
+    Oem { iana: u32, #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))] data: &'a [u8] },
+    Other(#[cfg_attr(feature = "serde", serde(with = "serde_bytes"))] &'a [u8])
+}
+
+/* 
+ * what a message carries, without what is inside. Ipmi20 only comes from
+ * `classify_bytes`, RMCP+ sessions are not decoded yet.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum RmcpKind {
+    Ack,
+    AsfPing,
+    AsfPong,
+    AsfOther,
+    Ipmi15,
+    Ipmi20,
+    Oem,
+    Other
 }
 
 impl<'a> BytesSerializationSized for RmcpMessage<'a> {
@@ -40,32 +68,93 @@ impl<'a> BytesSerializationSized for RmcpMessage<'a> {
 impl<'a> BytesSerializable for RmcpMessage<'a>  {
 
     fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error> {
+        self.write_into(slice, strict).map(|_| ())
+    }
+}
+
+impl<'a> RmcpMessage<'a>
+{
+    /* serialize to `slice` and return the number of bytes written */
+    pub fn write_into(&self, slice: &mut [u8], strict: bool) -> Result<usize, Error> {
+        let size = self.size();
+
+        if slice.len() < size {
+            return Err(Error::OutBufferTooSmall);
+        }
+
         slice[0] = 0x06;
         slice[1] = 0x00;
         slice[2] = self.sequence_number;
-        slice[3] = self.message_class;
+        slice[3] = match self.data {
+            RmcpContent::Ack => self.message_class | MSG_CLASS_ACK,
+            _ => self.message_class
+        };
         match &self.data {
             RmcpContent::Ack      => Ok(()),
             RmcpContent::Asf(asf) => asf.write_to_slice(&mut slice[4..], strict),
-            RmcpContent::Other(bytes) => Ok(slice[4..][..bytes.len()].copy_from_slice(bytes)),
+            RmcpContent::Other(bytes) => {
+                slice[4..][..bytes.len()].copy_from_slice(bytes);
+                Ok(())
+            },
             RmcpContent::Oem { iana, data } => {
                 slice[4..8].copy_from_slice(&iana.to_be_bytes());
                 slice[8..][..data.len()].copy_from_slice(data);
                 Ok(())
             },
             RmcpContent::Ipmi15(packet) => packet.write_to_slice(&mut slice[4..], strict)
-        }
+        }?;
+
+        Ok(size)
     }
-}
 
-impl<'a> RmcpMessage<'a>
-{
     pub fn from_ack(seqnum: u8) -> RmcpMessage<'a> {
         RmcpMessage {
             version: 0x06,
             reserved: 0x00,
             sequence_number: seqnum,
-            message_class: MSG_CLASS_ASF,
+            message_class: MSG_CLASS_ASF | MSG_CLASS_ACK,
+            data: RmcpContent::Ack
+        }
+    }
+
+    /* the class of the message, or the acknowledged class for an ACK */
+    pub fn class(&self) -> u8 {
+        self.message_class & 0b00001111
+    }
+
+    pub fn is_ack(&self) -> bool {
+        self.message_class & MSG_CLASS_ACK == MSG_CLASS_ACK
+    }
+
+    /* 
+     * whether the receiver should ACK this message, never for ACKs themselves
+     * nor for the RMCP_SEQ_NO_ACK sequence number, e.g. ASF ping/pong
+     */
+    pub fn expects_ack(&self) -> bool {
+        self.sequence_number != RMCP_SEQ_NO_ACK && !matches!(self.data, RmcpContent::Ack)
+    }
+
+    pub fn classify(&self) -> RmcpKind {
+        match &self.data {
+            RmcpContent::Ack => RmcpKind::Ack,
+            RmcpContent::Asf(asf) => match asf.data {
+                AsfData::Ping => RmcpKind::AsfPing,
+                AsfData::Pong { .. } => RmcpKind::AsfPong,
+                AsfData::Other(_) => RmcpKind::AsfOther
+            },
+            RmcpContent::Ipmi15(_) => RmcpKind::Ipmi15,
+            RmcpContent::Oem { .. } => RmcpKind::Oem,
+            RmcpContent::Other(_) => RmcpKind::Other
+        }
+    }
+
+    /* the ACK of `msg`, echoing its message class and sequence number */
+    pub fn ack_for(msg: &RmcpMessage) -> RmcpMessage<'a> {
+        RmcpMessage {
+            version: 0x06,
+            reserved: 0x00,
+            sequence_number: msg.sequence_number,
+            message_class: msg.message_class | MSG_CLASS_ACK,
             data: RmcpContent::Ack
         }
     }
@@ -74,70 +163,418 @@ impl<'a> RmcpMessage<'a>
         RmcpMessage {
             version: 0x06,
             reserved: 0x00,
-            sequence_number: 0xff,
+            sequence_number: RMCP_SEQ_NO_ACK,
             message_class: MSG_CLASS_ASF,
             data: RmcpContent::Asf(msg)
         }
     }
+
+    pub fn from_ipmi15(packet: Ipmi15Packet<'a>, seqnum: u8) -> RmcpMessage<'a> {
+        RmcpMessage {
+            version: 0x06,
+            reserved: 0x00,
+            sequence_number: seqnum,
+            message_class: MSG_CLASS_IPMI,
+            data: RmcpContent::Ipmi15(packet)
+        }
+    }
+
+    pub fn with_sequence_number(self, seqnum: u8) -> RmcpMessage<'a> {
+        RmcpMessage { sequence_number: seqnum, ..self }
+    }
 }
 
-impl<'a> BytesDeserializable<'a> for RmcpMessage<'a>
+/* 
+ * Hands out RMCP sequence numbers for frames that want to be ACKed. The
+ * usable space is 0-254, 255 is reserved for "no ack" and is never returned.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct RmcpSeqCounter {
+    next: u8
+}
+
+impl RmcpSeqCounter {
+    pub fn new() -> RmcpSeqCounter {
+        RmcpSeqCounter { next: 0 }
+    }
+
+    pub fn starting_at(seqnum: u8) -> RmcpSeqCounter {
+        let next = if seqnum == RMCP_SEQ_NO_ACK { 0 } else { seqnum };
+        RmcpSeqCounter { next }
+    }
+
+    pub fn next_seq(&mut self) -> u8 {
+        let seqnum = self.next;
+        self.next = if seqnum >= RMCP_SEQ_NO_ACK - 1 { 0 } else { seqnum + 1 };
+        seqnum
+    }
+
+    /* stamp `msg` with the next sequence number and return the number used */
+    pub fn stamp(&mut self, msg: &mut RmcpMessage) -> u8 {
+        let seqnum = self.next_seq();
+        msg.sequence_number = seqnum;
+        seqnum
+    }
+}
+
+impl<'a> RmcpContent<'a>
 {
-    fn from_bytes(bytes: &'a [u8], strict: bool) -> Result<RmcpMessage<'a>, Error>
+    /* decode the bytes following the RMCP header as a message of `class` */
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn from_class(message_class: u8, bytes: &'a [u8], strict: bool)
+        -> Result<RmcpContent<'a>, Error>
     {
-        if bytes.len() < 4 { return Err(Error::PayloadTooSmall); }
+        let mut idx = 0;
 
-        let version         = bytes[0];
-        let reserved        = bytes[1];
+        match message_class {
+            MSG_CLASS_OEM => {
+                if bytes.len() < 4 { return Err(Error::PayloadTooSmall); }
+                /* written big endian, like the IANA of an ASF message */
+                let iana = crate::take_be_u32!(bytes, idx);
+                let data = crate::take_remain!(bytes, idx);
+                let content = RmcpContent::Oem { iana, data };
+                Ok(content)
+            },
+            MSG_CLASS_ASF => {
+                AsfMessage::from_bytes(bytes, strict)
+                    .map(RmcpContent::Asf)
+            },
+            MSG_CLASS_IPMI => {
+                /* read ahead the auth format */
+                if bytes.first() == Some(&0x06) {
+                    /* Don't have support for RMCP+ / IPMI2 yet */
+                    parse_warn!("RMCP+ session (auth type 0x06) is not supported");
+                    Err(Error::UnsupportedProtocol)
+                } else {
+                    Ipmi15Packet::from_bytes(bytes, strict)
+                        .map(RmcpContent::Ipmi15)
+                }
+            },
+            _ => 
+                if strict { 
+                    parse_warn!("unsupported RMCP message class {=u8:#x}", message_class);
+                    Err(Error::UnsupportedProtocol)
+                } else {
+                    Ok(RmcpContent::Other(bytes))
+                }
+        }
+    }
+}
+
+/* length prefix in front of each IPMI message batched in an OEM payload */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum OemLengthPrefix {
+    U8,
+    U16Le,
+    U16Be
+}
 
-        if strict && (version != 0x06 || reserved != 0x00) {
-            if version != 0x06 { return Err(Error::InvalidRmcpVersionNumber(version)) }
-            if reserved != 0x00 { return Err(Error::InvalidRmcpReservedByte(reserved)) }
+impl OemLengthPrefix {
+    pub fn size(&self) -> usize {
+        match self {
+            OemLengthPrefix::U8 => 1,
+            OemLengthPrefix::U16Le | OemLengthPrefix::U16Be => 2
         }
+    }
 
-        let is_ack          = (bytes[3] & 0b10000000) == 0b10000000;
-        let sequence_number = bytes[2];
-        let message_class   = bytes[3] & 0b00001111;
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn read(&self, bytes: &[u8]) -> Option<usize> {
+        match (self, bytes) {
+            (OemLengthPrefix::U8, &[len, ..]) => Some(usize::from(len)),
+            (OemLengthPrefix::U16Le, &[lo, hi, ..]) => Some(usize::from(u16::from_le_bytes([lo, hi]))),
+            (OemLengthPrefix::U16Be, &[hi, lo, ..]) => Some(usize::from(u16::from_be_bytes([hi, lo]))),
+            _ => None
+        }
+    }
+}
 
-        let mut idx = 4;
+/* iterates length-prefixed IPMI messages, stops after the first error */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OemIpmiIter<'a> {
+    bytes:  &'a [u8],
+    prefix: OemLengthPrefix,
+    strict: bool
+}
 
-        let content = {
-            if is_ack {
-                Ok(RmcpContent::Ack)
-            } else {
-                match message_class {
-                    MSG_CLASS_OEM => {
-                        if bytes.len() < 8 { return Err(Error::PayloadTooSmall); }
-                        let iana = crate::take_le_u32!(bytes, idx);
-                        let data = crate::take_remain!(bytes, idx);
-                        let content = RmcpContent::Oem { iana, data };
-                        Ok(content)
-                    },
-                    MSG_CLASS_ASF => {
-                        AsfMessage::from_bytes(&bytes[4..], strict)
-                            .map(|m| RmcpContent::Asf(m))
-                    },
-                    MSG_CLASS_IPMI => {
-                        /* read ahead the auth format */
-                        if bytes[4] == 0x06 {
-                            /* Don't have support for RMCP+ / IPMI2 yet */
-                            Err(Error::UnsupportedProtocol)
-                        } else {
-                            Ipmi15Packet::from_bytes(&bytes[4..], strict)
-                                .map(|m| RmcpContent::Ipmi15(m))
-                        }
-                    },
-                    _ => 
-                        if strict { 
-                            Err(Error::UnsupportedProtocol)
-                        } else {
-                            Ok(RmcpContent::Other(&bytes[4..]))
-                        }
-                }
+impl<'a> OemIpmiIter<'a> {
+    pub fn new(bytes: &'a [u8], prefix: OemLengthPrefix, strict: bool) -> OemIpmiIter<'a> {
+        OemIpmiIter { bytes, prefix, strict }
+    }
+}
+
+impl<'a> Iterator for OemIpmiIter<'a> {
+    type Item = Result<IpmiMessage<'a>, Error>;
+
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let bytes  = self.bytes;
+        let header = self.prefix.size();
+        self.bytes = &[];
+
+        let split = self.prefix.read(bytes)
+            .and_then(|len| bytes.get(header..)?.split_at_checked(len));
+
+        let (msg, rest) = match split {
+            Some(split) => split,
+            None => return Some(Err(Error::PayloadTooSmall))
+        };
+
+        let msg = IpmiMessage::from_bytes(msg, self.strict);
+
+        if msg.is_ok() {
+            self.bytes = rest;
+        }
+
+        Some(msg)
+    }
+}
+
+impl<'a> RmcpContent<'a>
+{
+    /* the IPMI messages batched in an OEM payload, None if this is not OEM */
+    pub fn oem_ipmi_messages(&self, prefix: OemLengthPrefix, strict: bool) 
+        -> Option<OemIpmiIter<'a>>
+    {
+        match self {
+            RmcpContent::Oem { data, .. } => Some(OemIpmiIter::new(data, prefix, strict)),
+            _ => None
+        }
+    }
+}
+
+impl<'a> RmcpMessage<'a>
+{
+    /* 
+     * Like `from_bytes`, and an OEM message which IANA is in `decoders` has
+     * its data parsed by the matching decoder. Decoders are looked up in
+     * order, the first match is used.
+     */
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn from_bytes_with_oem<T>(bytes: &'a [u8], strict: bool, 
+                                  decoders: &[(u32, OemDecoder<'a, T>)])
+        -> Result<OemDecoded<'a, T>, Error>
+    {
+        let message = Self::from_bytes(bytes, strict)?;
+
+        let RmcpContent::Oem { iana, data } = message.data else {
+            return Ok(OemDecoded::Plain(message));
+        };
+
+        match decoders.iter().find(|(registered, _)| *registered == iana) {
+            Some((_, decode)) => decode(data, strict)
+                .map(|value| OemDecoded::Decoded { message, value }),
+            None => Ok(OemDecoded::Plain(message))
+        }
+    }
+
+    /* 
+     * Decode the RMCP message at the start of `bytes`, which may be followed
+     * by more data, and return it with the bytes past its end
+     */
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn from_bytes_partial(bytes: &'a [u8], strict: bool)
+        -> Result<(RmcpMessage<'a>, &'a [u8]), Error>
+    {
+        let (frame, rest) = bytes.split_at_checked(Self::frame_len(bytes)?)
+            .ok_or(Error::PayloadTooSmall)?;
+        Self::from_bytes(frame, strict).map(|msg| (msg, rest))
+    }
+
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn read_header(bytes: &[u8], strict: bool) -> Result<(u8, u8, u8, u8), Error>
+    {
+        let &[version, reserved, sequence_number, message_class, ..] = bytes else {
+            return Err(Error::PayloadTooSmall);
+        };
+
+        /* the version is checked first, a bad version hides a bad reserved byte */
+        if strict && version != 0x06 {
+            parse_warn!("RMCP version {=u8:#x} is not 0x06", version);
+            return Err(Error::InvalidRmcpVersionNumber(version));
+        }
+
+        if strict && reserved != 0x00 {
+            parse_warn!("RMCP reserved byte {=u8:#x} is not 0x00", reserved);
+            return Err(Error::InvalidRmcpReservedByte(reserved));
+        }
+
+        Ok((version, reserved, sequence_number, message_class & 0b00001111))
+    }
+}
+
+impl RmcpMessage<'_>
+{
+    /* 
+     * Cheap check on the version and reserved byte, to drop traffic which is
+     * obviously not RMCP before decoding it
+     */
+    pub fn looks_like_rmcp(bytes: &[u8]) -> bool {
+        bytes.len() >= 4 && bytes[0] == 0x06 && bytes[1] == 0x00
+    }
+
+    /* 
+     * Kind of the RMCP message at the start of `bytes` from its headers alone,
+     * without decoding the payload. None if the headers are cut short.
+     */
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn classify_bytes(bytes: &[u8]) -> Option<RmcpKind>
+    {
+        let &[_, _, _, message_class, ..] = bytes else {
+            return None;
+        };
+
+        if (message_class & MSG_CLASS_ACK) == MSG_CLASS_ACK {
+            return Some(RmcpKind::Ack);
+        }
+
+        Some(match message_class & 0b00001111 {
+            MSG_CLASS_ASF => match *bytes.get(8)? {
+                ASF_MSG_TYPE_PING => RmcpKind::AsfPing,
+                ASF_MSG_TYPE_PONG => RmcpKind::AsfPong,
+                _ => RmcpKind::AsfOther
+            },
+            /* the auth type tells an IPMI 1.5 session from a RMCP+ one */
+            MSG_CLASS_IPMI => match *bytes.get(4)? {
+                0x06 => RmcpKind::Ipmi20,
+                _ => RmcpKind::Ipmi15
+            },
+            MSG_CLASS_OEM => RmcpKind::Oem,
+            _ => RmcpKind::Other
+        })
+    }
+
+    /* 
+     * Length of the RMCP message at the start of `bytes`, worked out from the
+     * length fields of the headers. OEM and unknown classes carry no length
+     * and are assumed to take the remaining bytes.
+     */
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn frame_len(bytes: &[u8]) -> Result<usize, Error>
+    {
+        let &[_, _, _, message_class, ..] = bytes else {
+            return Err(Error::PayloadTooSmall);
+        };
+
+        let is_ack = (message_class & MSG_CLASS_ACK) == MSG_CLASS_ACK;
+
+        let len = if is_ack {
+            4
+        } else {
+            match message_class & 0b00001111 {
+                MSG_CLASS_ASF => {
+                    /* RMCP header + ASF header */
+                    let data_len = bytes.get(11).ok_or(Error::PayloadTooSmall)?;
+                    12 + usize::from(*data_len)
+                },
+                MSG_CLASS_IPMI => {
+                    /* RMCP header + session header, with auth code if any */
+                    let header = match bytes.get(4) {
+                        None => return Err(Error::PayloadTooSmall),
+                        Some(0x06) => return Err(Error::UnsupportedProtocol),
+                        Some(&IPMI_AUTH_TYPE_NONE) => 14,
+                        Some(_) => 30
+                    };
+                    let payload_len = bytes.get(header - 1).ok_or(Error::PayloadTooSmall)?;
+                    header + usize::from(*payload_len)
+                },
+                _ => bytes.len()
             }
         };
 
+        if bytes.len() < len {
+            Err(Error::PayloadTooSmall)
+        } else {
+            Ok(len)
+        }
+    }
+}
+
+/* 
+ * Iterates RMCP messages packed back to back in a buffer, stops after the
+ * first error.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RmcpFrames<'a> {
+    bytes:  &'a [u8],
+    strict: bool
+}
+
+impl<'a> RmcpFrames<'a> {
+    pub fn new(bytes: &'a [u8], strict: bool) -> RmcpFrames<'a> {
+        RmcpFrames { bytes, strict }
+    }
+}
+
+impl<'a> Iterator for RmcpFrames<'a> {
+    type Item = Result<RmcpMessage<'a>, Error>;
+
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let bytes  = self.bytes;
+        self.bytes = &[];
+
+        Some(RmcpMessage::from_bytes_partial(bytes, self.strict).map(|(msg, rest)| {
+            self.bytes = rest;
+            msg
+        }))
+    }
+}
+
+impl<'a> BytesDeserializable<'a> for RmcpMessage<'a>
+{
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn from_bytes(bytes: &'a [u8], strict: bool) -> Result<RmcpMessage<'a>, Error>
+    {
+        let (version, reserved, sequence_number, message_class) 
+            = Self::read_header(bytes, strict)?;
+
+        let is_ack = bytes.get(3).is_some_and(|class| (class & MSG_CLASS_ACK) == MSG_CLASS_ACK);
+        let bytes  = bytes.get(4..).ok_or(Error::PayloadTooSmall)?;
+
+        let content = if is_ack {
+                /* an ACK is the RMCP header alone */
+                if strict && !bytes.is_empty() {
+                    parse_warn!("RMCP ACK followed by {=usize} bytes", bytes.len());
+                    return Err(Error::PayloadTooLarge);
+                }
+                Ok(RmcpContent::Ack)
+            } else {
+                RmcpContent::from_class(message_class, bytes, strict)
+            };
+
+        /* keep the ACK bit so the message serializes back to an ACK */
+        let message_class = if is_ack { message_class | MSG_CLASS_ACK } else { message_class };
+
         content.map(|data| RmcpMessage {
             version, reserved, sequence_number, message_class, data, })
     }
 }
+
+#[cfg(feature = "alloc")]
+impl RmcpMessage<'_> {
+    pub fn to_vec(&self, strict: bool) -> Result<alloc::vec::Vec<u8>, Error> {
+        crate::ipmi::to_vec(self, strict)
+    }
+}
+
+/* ACKs, ASF and IPMI 1.5 messages that serialize in strict mode */
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for RmcpMessage<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let seqnum = u.arbitrary()?;
+
+        Ok(match u.int_in_range(0..=2)? {
+            0 => RmcpMessage::from_ack(seqnum),
+            1 => RmcpMessage::from_asf(u.arbitrary()?).with_sequence_number(seqnum),
+            _ => RmcpMessage::from_ipmi15(u.arbitrary()?, seqnum)
+        })
+    }
+}
@@ -0,0 +1,320 @@
+use macros::*;
+
+use crate::ipmi::summon_from_bytes;
+use crate::ipmi::{BytesDeserializable, BytesSerializationSized, BytesSerializable};
+use crate::ipmi::Error;
+use crate::ipmi::sol::PAYLOAD_TYPE_SOL;
+
+pub const PAYLOAD_TYPE_IPMI:         u8 = 0x00;
+pub const PAYLOAD_TYPE_OEM_EXPLICIT: u8 = 0x02;
+
+/* RMCP+ payload types of the session setup */
+pub const PAYLOAD_TYPE_OPEN_SESSION_REQUEST:  u8 = 0x10;
+pub const PAYLOAD_TYPE_OPEN_SESSION_RESPONSE: u8 = 0x11;
+pub const PAYLOAD_TYPE_RAKP1: u8 = 0x12;
+pub const PAYLOAD_TYPE_RAKP2: u8 = 0x13;
+pub const PAYLOAD_TYPE_RAKP3: u8 = 0x14;
+pub const PAYLOAD_TYPE_RAKP4: u8 = 0x15;
+
+pub const PAYLOAD_TYPE_ENCRYPTED:     u8 = 0b10000000;
+pub const PAYLOAD_TYPE_AUTHENTICATED: u8 = 0b01000000;
+
+/* what a RMCP+ payload carries, bits [5:0] of the payload type byte */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum PayloadKind {
+    Ipmi,
+    Sol,
+    /* the OEM IANA and payload id follow the session header */
+    OemExplicit,
+    OpenSessionRequest,
+    OpenSessionResponse,
+    Rakp1,
+    Rakp2,
+    Rakp3,
+    Rakp4,
+    /* 0x20-0x27 */
+    Oem(u8)
+}
+
+impl PayloadKind {
+    /* None for the reserved payload types */
+    pub fn from_u8(kind: u8) -> Option<PayloadKind> {
+        match kind {
+            PAYLOAD_TYPE_IPMI                  => Some(PayloadKind::Ipmi),
+            PAYLOAD_TYPE_SOL                   => Some(PayloadKind::Sol),
+            PAYLOAD_TYPE_OEM_EXPLICIT          => Some(PayloadKind::OemExplicit),
+            PAYLOAD_TYPE_OPEN_SESSION_REQUEST  => Some(PayloadKind::OpenSessionRequest),
+            PAYLOAD_TYPE_OPEN_SESSION_RESPONSE => Some(PayloadKind::OpenSessionResponse),
+            PAYLOAD_TYPE_RAKP1                 => Some(PayloadKind::Rakp1),
+            PAYLOAD_TYPE_RAKP2                 => Some(PayloadKind::Rakp2),
+            PAYLOAD_TYPE_RAKP3                 => Some(PayloadKind::Rakp3),
+            PAYLOAD_TYPE_RAKP4                 => Some(PayloadKind::Rakp4),
+            0x20..=0x27                        => Some(PayloadKind::Oem(kind)),
+            _ => None
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            PayloadKind::Ipmi                => PAYLOAD_TYPE_IPMI,
+            PayloadKind::Sol                 => PAYLOAD_TYPE_SOL,
+            PayloadKind::OemExplicit         => PAYLOAD_TYPE_OEM_EXPLICIT,
+            PayloadKind::OpenSessionRequest  => PAYLOAD_TYPE_OPEN_SESSION_REQUEST,
+            PayloadKind::OpenSessionResponse => PAYLOAD_TYPE_OPEN_SESSION_RESPONSE,
+            PayloadKind::Rakp1               => PAYLOAD_TYPE_RAKP1,
+            PayloadKind::Rakp2               => PAYLOAD_TYPE_RAKP2,
+            PayloadKind::Rakp3               => PAYLOAD_TYPE_RAKP3,
+            PayloadKind::Rakp4               => PAYLOAD_TYPE_RAKP4,
+            PayloadKind::Oem(kind)           => *kind
+        }
+    }
+}
+
+/* 
+ * The payload type byte of a RMCP+ session header, the payload kind with
+ * the encrypted and authenticated bits on top
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct PayloadType(pub u8);
+
+impl PayloadType {
+    pub fn new(kind: PayloadKind, encrypted: bool, authenticated: bool) -> PayloadType {
+        let mut value = kind.as_u8();
+        if encrypted {
+            value |= PAYLOAD_TYPE_ENCRYPTED;
+        }
+        if authenticated {
+            value |= PAYLOAD_TYPE_AUTHENTICATED;
+        }
+        PayloadType(value)
+    }
+
+    pub fn from_u8(value: u8) -> PayloadType {
+        PayloadType(value)
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        self.0
+    }
+
+    /* None for the reserved payload types */
+    pub fn kind(&self) -> Option<PayloadKind> {
+        PayloadKind::from_u8(self.0 & 0b00111111)
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.0 & PAYLOAD_TYPE_ENCRYPTED != 0
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.0 & PAYLOAD_TYPE_AUTHENTICATED != 0
+    }
+}
+
+/* `payload_type` of an algorithm payload */
+pub const ALGORITHM_PAYLOAD_AUTH:            u8 = 0x00;
+pub const ALGORITHM_PAYLOAD_INTEGRITY:       u8 = 0x01;
+pub const ALGORITHM_PAYLOAD_CONFIDENTIALITY: u8 = 0x02;
+
+pub const AUTH_ALG_RAKP_NONE:        u8 = 0x00;
+pub const AUTH_ALG_RAKP_HMAC_SHA1:   u8 = 0x01;
+pub const AUTH_ALG_RAKP_HMAC_MD5:    u8 = 0x02;
+pub const AUTH_ALG_RAKP_HMAC_SHA256: u8 = 0x03;
+
+pub const INTEGRITY_ALG_NONE:            u8 = 0x00;
+pub const INTEGRITY_ALG_HMAC_SHA1_96:    u8 = 0x01;
+pub const INTEGRITY_ALG_HMAC_MD5_128:    u8 = 0x02;
+pub const INTEGRITY_ALG_MD5_128:         u8 = 0x03;
+pub const INTEGRITY_ALG_HMAC_SHA256_128: u8 = 0x04;
+
+pub const CONFIDENTIALITY_ALG_NONE:        u8 = 0x00;
+pub const CONFIDENTIALITY_ALG_AES_CBC_128: u8 = 0x01;
+
+/* one of the three algorithms proposed in, or picked by, Open Session */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[bytes_serialize(size = 8)]
+pub struct AlgorithmPayload {
+    pub payload_type: u8,
+    pub reserved0: [u8; 2],
+    pub payload_len: u8,
+    /* bits [5:0] */
+    pub algorithm: u8,
+    pub reserved1: [u8; 3]
+}
+
+impl AlgorithmPayload {
+    pub fn new(payload_type: u8, algorithm: u8) -> AlgorithmPayload {
+        AlgorithmPayload {
+            payload_type,
+            reserved0: [0; 2],
+            payload_len: 8,
+            algorithm,
+            reserved1: [0; 3]
+        }
+    }
+
+    pub fn auth(algorithm: u8) -> AlgorithmPayload {
+        AlgorithmPayload::new(ALGORITHM_PAYLOAD_AUTH, algorithm)
+    }
+
+    pub fn integrity(algorithm: u8) -> AlgorithmPayload {
+        AlgorithmPayload::new(ALGORITHM_PAYLOAD_INTEGRITY, algorithm)
+    }
+
+    pub fn confidentiality(algorithm: u8) -> AlgorithmPayload {
+        AlgorithmPayload::new(ALGORITHM_PAYLOAD_CONFIDENTIALITY, algorithm)
+    }
+
+    pub fn algorithm_id(&self) -> u8 {
+        self.algorithm & 0b00111111
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct OpenSessionRequest {
+    pub message_tag: u8,
+    /* bits [3:0], 0 asks for the highest level allowed by the cipher suite */
+    pub max_priv_level: u8,
+    pub reserved: [u8; 2],
+
+    #[bytes_serialize(endian = "le")]
+    pub console_session_id: u32,
+
+    pub auth: AlgorithmPayload,
+    pub integrity: AlgorithmPayload,
+    pub confidentiality: AlgorithmPayload
+}
+
+/* 
+ * Only a response with a zero status code carries the session id and the
+ * algorithms, an error response stops after the console session id and
+ * leaves them None.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct OpenSessionResponse {
+    pub message_tag: u8,
+    /* RAKP status code */
+    pub status_code: u8,
+    pub max_priv_level: u8,
+    pub reserved: u8,
+    pub console_session_id: u32,
+    pub managed_session_id: Option<u32>,
+    pub auth: Option<AlgorithmPayload>,
+    pub integrity: Option<AlgorithmPayload>,
+    pub confidentiality: Option<AlgorithmPayload>
+}
+
+impl OpenSessionResponse {
+    /* the session id and algorithms, if all of them are present */
+    fn accepted(&self) -> Option<(u32, &AlgorithmPayload, &AlgorithmPayload, &AlgorithmPayload)> {
+        match (&self.managed_session_id, &self.auth, &self.integrity, &self.confidentiality) {
+            (Some(id), Some(auth), Some(integrity), Some(confidentiality)) =>
+                Some((*id, auth, integrity, confidentiality)),
+            _ => None
+        }
+    }
+}
+
+impl BytesSerializationSized for OpenSessionResponse {
+    fn size(&self) -> usize {
+        match self.accepted() {
+            Some(_) => 36,
+            None => 8
+        }
+    }
+}
+
+impl BytesSerializable for OpenSessionResponse {
+    fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error> {
+        if slice.len() < self.size() {
+            return Err(Error::OutBufferTooSmall);
+        }
+
+        let accepted = self.accepted();
+        let partial = accepted.is_none() && (self.managed_session_id.is_some() || self.auth.is_some()
+            || self.integrity.is_some() || self.confidentiality.is_some());
+
+        if partial || (strict && accepted.is_some() != (self.status_code == 0)) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        slice[0] = self.message_tag;
+        slice[1] = self.status_code;
+        slice[2] = self.max_priv_level;
+        slice[3] = 0x00;
+        slice[4..8].copy_from_slice(&self.console_session_id.to_le_bytes());
+
+        if let Some((id, auth, integrity, confidentiality)) = accepted {
+            slice[8..12].copy_from_slice(&id.to_le_bytes());
+            auth.write_to_slice(&mut slice[12..20], strict)?;
+            integrity.write_to_slice(&mut slice[20..28], strict)?;
+            confidentiality.write_to_slice(&mut slice[28..36], strict)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BytesDeserializable<'_> for OpenSessionResponse {
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    fn from_bytes(bytes: &[u8], strict: bool) -> Result<OpenSessionResponse, Error> {
+        let mut idx = 0;
+        let message_tag    = crate::take_u8!(bytes, idx);
+        let status_code    = crate::take_u8!(bytes, idx);
+        let max_priv_level = crate::take_u8!(bytes, idx);
+        let reserved       = crate::take_u8!(bytes, idx);
+        let console_session_id = crate::take_le_u32!(bytes, idx);
+
+        if strict && reserved != 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let mut res = OpenSessionResponse {
+            message_tag, status_code, max_priv_level, reserved, console_session_id,
+            managed_session_id: None, auth: None, integrity: None, confidentiality: None
+        };
+
+        if status_code == 0 {
+            res.managed_session_id = Some(crate::take_le_u32!(bytes, idx));
+            let mut algorithm = || -> Result<AlgorithmPayload, Error> {
+                let payload = AlgorithmPayload::from_bytes(bytes.get(idx..).unwrap_or_default(), strict)?;
+                idx += payload.size();
+                Ok(payload)
+            };
+            res.auth = Some(algorithm()?);
+            res.integrity = Some(algorithm()?);
+            res.confidentiality = Some(algorithm()?);
+        }
+
+        Ok(res)
+    }
+}
+
+/* next header byte of the RMCP+ session trailer */
+pub const RMCPP_NEXT_HEADER: u8 = 0x07;
+
+/* 
+ * Write the session trailer of an authenticated RMCP+ packet after the
+ * first `payload_len` bytes of `buf`:
+ *
+ *   0xff pad (0-3) | pad length (1) | next header (1) | `auth` code
+ *
+ * the pad aligns everything up to the next header to 4 bytes. Returns the
+ * length of the packet with the trailer.
+ */
+pub fn append_integrity_trailer(buf: &mut [u8], payload_len: usize, auth: &[u8]) -> Result<usize, Error> {
+    let pad_len = crate::ipmi::rsp::rsp_pad_len(payload_len);
+    let total = payload_len + usize::from(pad_len) + 2 + auth.len();
+
+    let Some(trailer) = buf.get_mut(payload_len..total) else {
+        return Err(Error::OutBufferTooSmall);
+    };
+
+    let (pad, rest) = trailer.split_at_mut(usize::from(pad_len));
+    pad.fill(0xff);
+    rest[0] = pad_len;
+    rest[1] = RMCPP_NEXT_HEADER;
+    rest[2..].copy_from_slice(auth);
+
+    Ok(total)
+}
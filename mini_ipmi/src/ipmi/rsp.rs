@@ -0,0 +1,188 @@
+use crate::ipmi::*;
+use crate::ipmi::rmcp::RmcpMessage;
+use crate::ipmi::util::parse_warn;
+
+/* Next Header of a RSP trailer protecting a RMCP message */
+pub const RSP_NEXT_HEADER_RMCP: u8 = 0x06;
+
+/*
+ * RMCP Security-Extensions Protocol (ASF 2.0) wraps a whole RMCP message,
+ * its header included, with a session header and an integrity trailer.
+ *
+ * +----Field------(size)-+
+ * | Session ID       (4) |
+ * | Sequence Number  (4) |
+ * | Payload        (var) |
+ * | Pad            (0-3) |
+ * | Pad Length       (1) |
+ * | Next Header      (1) |
+ * | Integrity Data (var) |
+ * +----------------------+
+ *
+ * The payload is the protected RMCP message, and `next_header` is
+ * `RSP_NEXT_HEADER_RMCP`.
+ */
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RspMessage<'a> {
+    pub session_id:  u32,
+    pub seqnum:      u32,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub payload:     &'a [u8],
+    pub pad_len:     u8,
+    pub next_header: u8,
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pub integrity:   &'a [u8]
+}
+
+/* pad needed so that payload + pad + pad length + next header is 4-aligned */
+pub fn rsp_pad_len(payload_len: usize) -> u8 {
+    ((4 - (payload_len + 2) % 4) % 4) as u8
+}
+
+impl<'a> RspMessage<'a>
+{
+    pub fn new(session_id: u32, seqnum: u32, payload: &'a [u8], integrity: &'a [u8])
+        -> RspMessage<'a>
+    {
+        RspMessage {
+            session_id,
+            seqnum,
+            payload,
+            pad_len: rsp_pad_len(payload.len()),
+            next_header: RSP_NEXT_HEADER_RMCP,
+            integrity
+        }
+    }
+
+    /* decode the protected RMCP message */
+    pub fn message(&self, strict: bool) -> Result<RmcpMessage<'a>, Error> {
+        RmcpMessage::from_bytes(self.payload, strict)
+    }
+
+    /*
+     * Whether `bytes` looks like a RSP datagram rather than a plain RMCP one:
+     * a RMCP header right after the session header, and a trailer naming
+     * RMCP as the next header.
+     */
+    pub fn looks_like_rsp(bytes: &[u8], integrity_len: usize) -> bool {
+        let next_header = bytes.len().checked_sub(integrity_len.saturating_add(1))
+            .and_then(|idx| bytes.get(idx));
+
+        bytes.len() >= integrity_len.saturating_add(10)
+            && next_header == Some(&RSP_NEXT_HEADER_RMCP)
+            && bytes.get(8..).is_some_and(RmcpMessage::looks_like_rmcp)
+    }
+
+    #[deny(clippy::indexing_slicing, clippy::unwrap_used)]
+    pub fn from_bytes(bytes: &'a [u8], integrity_len: usize, strict: bool)
+        -> Result<RspMessage<'a>, Error>
+    {
+        /* 8 bytes header, pad length and next header */
+        if bytes.len() < integrity_len.saturating_add(10) {
+            return Err(Error::PayloadTooSmall);
+        }
+
+        let mut idx    = 0;
+        let session_id = crate::take_be_u32!(bytes, idx);
+        let seqnum     = crate::take_be_u32!(bytes, idx);
+
+        let rest = crate::take_remain!(bytes, idx);
+        let (rest, integrity) = rest.split_at_checked(rest.len() - integrity_len)
+            .ok_or(Error::PayloadTooSmall)?;
+        let (rest, trailer)   = rest.split_at_checked(rest.len() - 2)
+            .ok_or(Error::PayloadTooSmall)?;
+
+        let &[pad_len, next_header] = trailer else {
+            return Err(Error::PayloadTooSmall);
+        };
+
+        let payload = rest.len().checked_sub(usize::from(pad_len))
+            .and_then(|len| rest.get(..len))
+            .ok_or(Error::PayloadTooSmall)?;
+
+        if strict && pad_len != rsp_pad_len(payload.len()) {
+            return Err(Error::ExpectedSizeMismatch {
+                expected: usize::from(rsp_pad_len(payload.len())), actual: usize::from(pad_len) });
+        }
+
+        if strict && next_header != RSP_NEXT_HEADER_RMCP {
+            parse_warn!("unsupported RSP next header {=u8:#x}", next_header);
+            return Err(Error::UnsupportedProtocol);
+        }
+
+        Ok(RspMessage { session_id, seqnum, payload, pad_len, next_header, integrity })
+    }
+}
+
+impl BytesSerializationSized for RspMessage<'_> {
+    fn size(&self) -> usize {
+        8 + self.payload.len() + usize::from(self.pad_len) + 2 + self.integrity.len()
+    }
+}
+
+impl BytesSerializable for RspMessage<'_>
+{
+    fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
+    {
+        if slice.len() < self.size() {
+            return Err(Error::OutBufferTooSmall);
+        }
+
+        if strict && self.pad_len != rsp_pad_len(self.payload.len()) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let pad_len = usize::from(self.pad_len);
+
+        slice[0..4].copy_from_slice(&self.session_id.to_be_bytes());
+        slice[4..8].copy_from_slice(&self.seqnum.to_be_bytes());
+
+        let idx = 8 + self.payload.len();
+        slice[8..idx].copy_from_slice(self.payload);
+        slice[idx..][..pad_len].fill(0);
+
+        let idx = idx + pad_len;
+        slice[idx] = self.pad_len;
+        slice[idx + 1] = self.next_header;
+        slice[(idx + 2)..][..self.integrity.len()].copy_from_slice(self.integrity);
+
+        Ok(())
+    }
+}
+
+/*
+ * A datagram received on the RMCP port, either a plain RMCP message or one
+ * protected by RSP.
+ */
+#[derive(PartialEq, Eq, Debug)]
+pub enum Datagram<'a> {
+    Rmcp(RmcpMessage<'a>),
+    Rsp(RspMessage<'a>)
+}
+
+impl<'a> Datagram<'a>
+{
+    /*
+     * Decode `bytes` as RSP when `looks_like_rsp` says so, as plain RMCP
+     * otherwise. `integrity_len` is the length of the integrity data of the
+     * RSP session, if any.
+     */
+    pub fn from_bytes(bytes: &'a [u8], integrity_len: usize, strict: bool)
+        -> Result<Datagram<'a>, Error>
+    {
+        if RspMessage::looks_like_rsp(bytes, integrity_len) {
+            RspMessage::from_bytes(bytes, integrity_len, strict).map(Datagram::Rsp)
+        } else {
+            RmcpMessage::from_bytes(bytes, strict).map(Datagram::Rmcp)
+        }
+    }
+
+    /* the RMCP message, unwrapped from RSP if needed */
+    pub fn into_message(self, strict: bool) -> Result<RmcpMessage<'a>, Error> {
+        match self {
+            Datagram::Rmcp(msg) => Ok(msg),
+            Datagram::Rsp(rsp)  => rsp.message(strict)
+        }
+    }
+}
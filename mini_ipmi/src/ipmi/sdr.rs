@@ -0,0 +1,242 @@
+use macros::*;
+
+use crate::ipmi::summon_from_bytes;
+use crate::ipmi::{BytesDeserializable, BytesSerializationSized, BytesSerializable};
+use crate::ipmi::{CallError, Error, IPMI_MAX_PAYLOAD};
+use crate::ipmi::cmd::{GetSdrRequest, GetSdrResponse};
+use crate::ipmi::completion::CompletionCode;
+
+pub const SDR_RECORD_TYPE_FULL:    u8 = 0x01;
+pub const SDR_RECORD_TYPE_COMPACT: u8 = 0x02;
+
+/* record id of the first record in Get SDR requests */
+pub const SDR_FIRST_RECORD: u16 = 0x0000;
+/* next record id of the last record */
+pub const SDR_LAST_RECORD:  u16 = 0xffff;
+
+/* size of the header common to every SDR, the last byte is the body length */
+pub const SDR_HEADER_SIZE: usize = 5;
+
+pub const SDR_ANALOG_FORMAT_UNSIGNED:  u8 = 0b00;
+pub const SDR_ANALOG_FORMAT_ONES_COMP: u8 = 0b01;
+pub const SDR_ANALOG_FORMAT_TWOS_COMP: u8 = 0b10;
+pub const SDR_ANALOG_FORMAT_NONE:      u8 = 0b11;
+
+/* Full Sensor Record (type 0x01), including the 5 bytes record header */
+#[derive(Debug, PartialEq, Eq, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SdrFullRecord<'a> {
+    #[bytes_serialize(endian = "le")]
+    pub record_id: u16,
+    pub sdr_version: u8,
+    pub record_type: u8,
+    pub record_length: u8,
+
+    pub owner_id: u8,
+    pub owner_lun: u8,
+    pub sensor_number: u8,
+    pub entity_id: u8,
+    pub entity_instance: u8,
+    pub sensor_init: u8,
+    pub sensor_caps: u8,
+    pub sensor_type: u8,
+    pub event_reading_type: u8,
+
+    #[bytes_serialize(endian = "le")]
+    pub assertion_mask: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub deassertion_mask: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub reading_mask: u16,
+
+    pub units: u8,
+    pub base_unit: u8,
+    pub modifier_unit: u8,
+    pub linearization: u8,
+    pub m_lsb: u8,
+    pub m_msb_tolerance: u8,
+    pub b_lsb: u8,
+    pub b_msb_accuracy: u8,
+    pub accuracy_direction: u8,
+    pub r_b_exp: u8,
+
+    pub analog_flags: u8,
+    pub nominal_reading: u8,
+    pub normal_max: u8,
+    pub normal_min: u8,
+    pub sensor_max: u8,
+    pub sensor_min: u8,
+    pub upper_non_recoverable: u8,
+    pub upper_critical: u8,
+    pub upper_non_critical: u8,
+    pub lower_non_recoverable: u8,
+    pub lower_critical: u8,
+    pub lower_non_critical: u8,
+    pub positive_hysteresis: u8,
+    pub negative_hysteresis: u8,
+    pub reserved: [u8; 2],
+    pub oem: u8,
+    pub id_string_type_len: u8,
+    pub id_string: &'a [u8]
+}
+
+fn sign_extend(value: u16, bits: u32) -> i16 {
+    let shift = 16 - bits;
+    ((value << shift) as i16) >> shift
+}
+
+impl SdrFullRecord<'_> {
+    pub fn analog_format(&self) -> u8 {
+        self.units >> 6
+    }
+
+    /* 10 bits two's complement */
+    pub fn m(&self) -> i16 {
+        sign_extend(u16::from(self.m_lsb) | (u16::from(self.m_msb_tolerance >> 6) << 8), 10)
+    }
+
+    /* 10 bits two's complement */
+    pub fn b(&self) -> i16 {
+        sign_extend(u16::from(self.b_lsb) | (u16::from(self.b_msb_accuracy >> 6) << 8), 10)
+    }
+
+    /* 4 bits two's complement */
+    pub fn r_exp(&self) -> i8 {
+        sign_extend(u16::from(self.r_b_exp >> 4), 4) as i8
+    }
+
+    /* 4 bits two's complement */
+    pub fn b_exp(&self) -> i8 {
+        sign_extend(u16::from(self.r_b_exp & 0x0f), 4) as i8
+    }
+
+    /* interpret the raw reading according to the analog data format */
+    pub fn raw_value(&self, raw: u8) -> i32 {
+        match self.analog_format() {
+            SDR_ANALOG_FORMAT_ONES_COMP if raw & 0x80 == 0x80 => -i32::from(!raw),
+            SDR_ANALOG_FORMAT_TWOS_COMP => i32::from(raw as i8),
+            _ => i32::from(raw)
+        }
+    }
+
+    /* 
+     * Convert a raw reading with y = (M * x + B * 10^Bexp) * 10^Rexp without
+     * going through floating point. The reading is `value * 10^exp`, in case
+     * the value does not fit in 32 bits, precision is dropped from the 
+     * least significant digits.
+     *
+     * Linearization functions other than linear are not applied.
+     */
+    pub fn convert_reading_fixed(&self, raw: u8) -> (i32, i8) {
+        let x     = i64::from(self.raw_value(raw));
+        let m     = i64::from(self.m());
+        let b     = i64::from(self.b());
+        let b_exp = self.b_exp();
+        let mut exp = self.r_exp();
+
+        /* 
+         * |M * x| < 2^17 and |B * 10^Bexp| < 2^33, both fit comfortably
+         * in 64 bits regardless of the sign of Bexp
+         */
+        let mut value = if b_exp >= 0 {
+            m * x + b * 10i64.pow(b_exp as u32)
+        } else {
+            exp += b_exp;
+            m * x * 10i64.pow(b_exp.unsigned_abs() as u32) + b
+        };
+
+        while i32::try_from(value).is_err() {
+            value /= 10;
+            exp += 1;
+        }
+
+        (value as i32, exp)
+    }
+}
+
+/* 
+ * Reads a SDR that may not fit in a single Get SDR response, by issuing
+ * reads of `chunk_size` bytes at increasing offsets.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SdrReader {
+    pub reservation_id: u16,
+    pub chunk_size: u8,
+    /* number of times to re-reserve if the reservation is cancelled */
+    pub max_retries: usize
+}
+
+impl SdrReader {
+    pub fn new(reservation_id: u16, chunk_size: u8) -> SdrReader {
+        SdrReader { reservation_id, chunk_size, max_retries: 3 }
+    }
+
+    /*
+     * Assemble record `record_id` into `out`, return the id of the next 
+     * record and the size of the record.
+     *
+     * `get_sdr` performs the Get SDR round trip, it writes the response data
+     * following the completion code into the buffer and returns the 
+     * completion code with the number of bytes written. `reserve` is called
+     * to obtain a new reservation when the current one was cancelled, in
+     * which case the record is read again from the start.
+     */
+    pub fn read_record<E, G, R>(&mut self, record_id: u16, out: &mut [u8],
+                                mut get_sdr: G, mut reserve: R)
+        -> Result<(u16, usize), CallError<E>>
+        where G: FnMut(&GetSdrRequest, &mut [u8]) -> Result<(u8, usize), E>,
+              R: FnMut() -> Result<u16, E>
+    {
+        let mut scratch = [0u8; 2 + IPMI_MAX_PAYLOAD];
+        let mut retries = 0;
+        let mut offset  = 0usize;
+        let mut total: Option<usize> = None;
+
+        loop {
+            let remain = total.map(|t| t - offset).unwrap_or(usize::MAX);
+            let req = GetSdrRequest {
+                reservation_id: self.reservation_id,
+                record_id,
+                offset: u8::try_from(offset).map_err(|_| CallError::Parse(Error::PayloadTooLarge))?,
+                bytes_to_read: remain.min(usize::from(self.chunk_size)) as u8
+            };
+
+            let (code, len) = get_sdr(&req, &mut scratch).map_err(CallError::Transport)?;
+
+            if CompletionCode(code) == CompletionCode::RESERVATION_CANCELLED && retries < self.max_retries {
+                retries += 1;
+                self.reservation_id = reserve().map_err(CallError::Transport)?;
+                offset = 0;
+                total  = None;
+                continue;
+            } else if code != 0 {
+                return Err(CallError::CompletionCode(code));
+            }
+
+            let res = GetSdrResponse::from_bytes(&scratch[..len.min(scratch.len())], true)
+                .map_err(CallError::Parse)?;
+
+            if res.data.is_empty() {
+                return Err(CallError::Parse(Error::PayloadTooSmall));
+            }
+
+            let data = &res.data[..res.data.len().min(remain)];
+
+            if out.len() < offset + data.len() {
+                return Err(CallError::Parse(Error::OutBufferTooSmall));
+            }
+
+            out[offset..][..data.len()].copy_from_slice(data);
+            offset += data.len();
+
+            if total.is_none() && offset >= SDR_HEADER_SIZE {
+                total = Some(SDR_HEADER_SIZE + usize::from(out[SDR_HEADER_SIZE - 1]));
+            }
+
+            if total.is_some_and(|t| offset >= t) {
+                return Ok((res.next_record_id, offset));
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use macros::*;
+
+use crate::ipmi::{serialize_to_array, summon_from_bytes};
+use crate::ipmi::{BytesDeserializable, BytesSerializationSized, BytesSerializable};
+use crate::ipmi::{CallError, Error};
+use crate::ipmi::cmd::{GetSelEntryRequest, GetSelEntryResponse};
+use crate::ipmi::ipmi::{IpmiData, IpmiMessage};
+
+pub const SEL_RECORD_TYPE_SYSTEM_EVENT: u8 = 0x02;
+
+/* record id of the first entry in Get SEL Entry requests */
+pub const SEL_FIRST_ENTRY: u16 = 0x0000;
+/* next record id of the last entry */
+pub const SEL_LAST_ENTRY:  u16 = 0xffff;
+
+/* a 16 bytes SEL record, `data` is interpreted according to `record_type` */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+#[bytes_serialize(size = 16)]
+pub struct SelRecord {
+    #[bytes_serialize(endian = "le")]
+    pub record_id: u16,
+    pub record_type: u8,
+    pub data: [u8; 13]
+}
+
+/* 
+ * Walks the SEL one Get SEL Entry at a time, following the next record id
+ * until the last entry.
+ *
+ * `transport` performs the round trip of a request message and returns the
+ * response message. Iteration stops after the first error.
+ */
+pub struct SelIterator<'b, F> {
+    pub reservation_id: u16,
+    pub peer_addr: u8,
+    pub local_addr: u8,
+    pub transport: F,
+    seqnum: u8,
+    next_record_id: Option<u16>,
+    _response: core::marker::PhantomData<&'b [u8]>
+}
+
+impl<'b, F> SelIterator<'b, F> {
+    /* walk the whole SEL from the BMC (0x20) as remote console software (0x81) */
+    pub fn new(reservation_id: u16, transport: F) -> SelIterator<'b, F> {
+        SelIterator {
+            reservation_id,
+            peer_addr: 0x20,
+            local_addr: 0x81,
+            transport,
+            seqnum: 0,
+            next_record_id: Some(SEL_FIRST_ENTRY),
+            _response: core::marker::PhantomData
+        }
+    }
+
+    fn fetch<E>(&mut self, record_id: u16) -> Result<SelRecord, CallError<E>>
+        where F: FnMut(&IpmiMessage) -> Result<IpmiMessage<'b>, E>
+    {
+        let req = GetSelEntryRequest {
+            reservation_id: self.reservation_id,
+            record_id,
+            offset: 0,
+            /* whole record */
+            bytes_to_read: 0xff
+        };
+        let (data, len) = serialize_to_array::<_, 6>(&req, true).map_err(CallError::Parse)?;
+
+        let msg = IpmiMessage {
+            peer_addr: self.peer_addr,
+            netfn: 0x0a,
+            peer_lun: 0,
+            local_addr: self.local_addr,
+            seqnum: self.seqnum,
+            local_lun: 0,
+            cmd: 0x43,
+            data: IpmiData::Request(&data[..len])
+        };
+        self.seqnum = (self.seqnum + 1) & 0b00111111;
+
+        let res = (self.transport)(&msg).map_err(CallError::Transport)?;
+
+        let IpmiData::Response(code, data) = res.data else {
+            return Err(CallError::Parse(Error::InvalidConfiguration));
+        };
+
+        if code != 0 {
+            return Err(CallError::CompletionCode(code));
+        }
+
+        let res: GetSelEntryResponse = summon_from_bytes(data, true).map_err(CallError::Parse)?;
+        let record = summon_from_bytes(&res.record, true).map_err(CallError::Parse)?;
+
+        self.next_record_id = Some(res.next_record_id).filter(|id| *id != SEL_LAST_ENTRY);
+        Ok(record)
+    }
+}
+
+impl<'b, E, F> Iterator for SelIterator<'b, F>
+    where F: FnMut(&IpmiMessage) -> Result<IpmiMessage<'b>, E>
+{
+    type Item = Result<SelRecord, CallError<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record_id = self.next_record_id.take()?;
+        Some(self.fetch(record_id))
+    }
+}
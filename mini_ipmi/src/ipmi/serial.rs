@@ -0,0 +1,92 @@
+use crate::ipmi::ipmi::IpmiMessage;
+use crate::ipmi::*;
+use crate::ipmi::util::parse_warn;
+
+/* special characters of IPMI over serial, Basic Mode */
+pub const BASIC_MODE_START:     u8 = 0xa0;
+pub const BASIC_MODE_STOP:      u8 = 0xa5;
+pub const BASIC_MODE_HANDSHAKE: u8 = 0xa6;
+pub const BASIC_MODE_ESCAPE:    u8 = 0xaa;
+/* escaped so the frame does not confuse a modem */
+pub const BASIC_MODE_ESC:       u8 = 0x1b;
+
+/* (character, what follows BASIC_MODE_ESCAPE in its place) */
+const ESCAPED: [(u8, u8); 5] = [
+    (BASIC_MODE_START,     0xb0),
+    (BASIC_MODE_STOP,      0xb5),
+    (BASIC_MODE_HANDSHAKE, 0xb6),
+    (BASIC_MODE_ESCAPE,    0xba),
+    (BASIC_MODE_ESC,       0x3b)
+];
+
+/*
+ * Write `msg` to `out` between the start and stop characters, escaping the
+ * special characters in the message, and return the length of the frame.
+ */
+pub fn frame_basic_mode(msg: &IpmiMessage, out: &mut [u8]) -> Result<usize, Error> {
+    let mut raw = [0u8; IPMI_MAX_PAYLOAD];
+    let raw = raw.get_mut(..msg.size()).ok_or(Error::PayloadTooLarge)?;
+    msg.write_to_slice(raw, true)?;
+
+    let mut len = 0;
+    let mut push = |byte: u8| -> Result<(), Error> {
+        *out.get_mut(len).ok_or(Error::OutBufferTooSmall)? = byte;
+        len += 1;
+        Ok(())
+    };
+
+    push(BASIC_MODE_START)?;
+    for &byte in raw.iter() {
+        match ESCAPED.iter().find(|(c, _)| *c == byte) {
+            Some((_, escaped)) => {
+                push(BASIC_MODE_ESCAPE)?;
+                push(*escaped)?;
+            },
+            None => push(byte)?
+        }
+    }
+    push(BASIC_MODE_STOP)?;
+
+    Ok(len)
+}
+
+/*
+ * Decode the first frame in `bytes`, unescaping the message into `buf`
+ * which the returned message borrows from. Handshakes and anything else
+ * before the start character are skipped, a start character in the middle
+ * of a frame starts over. A frame without its stop character, or ending on
+ * an escape, is `PayloadTooSmall`.
+ */
+pub fn deframe_basic_mode<'a>(bytes: &[u8], buf: &'a mut [u8]) -> Result<IpmiMessage<'a>, Error> {
+    let start = bytes.iter().position(|b| *b == BASIC_MODE_START).ok_or(Error::PayloadTooSmall)?;
+
+    let mut len = 0;
+    let mut iter = bytes.iter().skip(start + 1);
+
+    loop {
+        let byte = match *iter.next().ok_or(Error::PayloadTooSmall)? {
+            BASIC_MODE_STOP => break,
+            BASIC_MODE_START => {
+                len = 0;
+                continue;
+            },
+            BASIC_MODE_ESCAPE => {
+                let escaped = *iter.next().ok_or(Error::PayloadTooSmall)?;
+                match ESCAPED.iter().find(|(_, e)| *e == escaped) {
+                    Some((c, _)) => *c,
+                    None => {
+                        parse_warn!("invalid Basic Mode escape {=u8:#x}", escaped);
+                        return Err(Error::InvalidConfiguration);
+                    }
+                }
+            },
+            byte => byte
+        };
+
+        *buf.get_mut(len).ok_or(Error::OutBufferTooSmall)? = byte;
+        len += 1;
+    }
+
+    let buf: &'a [u8] = buf;
+    IpmiMessage::from_bytes(&buf[..len], true)
+}
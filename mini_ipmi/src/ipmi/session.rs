@@ -0,0 +1,185 @@
+use crate::ipmi::cmd::*;
+use crate::ipmi::ipmi::*;
+use crate::ipmi::{serialize_to_array, BytesSerializable, Error};
+
+/* where an IPMI 1.5 session establishment is at */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SessionState {
+    Unauthenticated,
+    ChannelCapsQueried,
+    ChallengeReceived { tmp_session_id: u32, challenge: [u8; 16] },
+    SessionActive { session_id: u32, outbound_seq: u32 }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionError {
+    Parse(Error),
+    CompletionCode(u8),
+    /* the BMC does not offer the requested authentication type */
+    AuthTypeUnsupported(u8),
+    /* not the response to the last request */
+    UnexpectedMessage
+}
+
+impl From<Error> for SessionError {
+    fn from(error: Error) -> SessionError {
+        SessionError::Parse(error)
+    }
+}
+
+/*
+ * Drives the IPMI 1.5 session establishment:
+ *   Get Channel Authentication Capabilities
+ *   Get Session Challenge
+ *   Activate Session
+ *   Set Session Privilege Level
+ *
+ * `start` returns the first request, then each response fed to `handle`
+ * returns the next request until the session is active at the requested
+ * privilege level. Requests are returned rather than sent, the caller wraps
+ * them in a `Ipmi15Packet` with the id and sequence number of
+ * `packet_header`, and computes the auth code if any.
+ */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Session {
+    pub state: SessionState,
+    pub auth_type: u8,
+    pub priv_level: PrivLevel,
+    pub username: [u8; 16],
+    /* the first sequence number the BMC uses toward us */
+    pub init_outbound_seq: u32,
+    pub peer_addr: u8,
+    pub local_addr: u8,
+    rq_seq: u8,
+    /* request data of the last message returned */
+    request: [u8; 22],
+    request_len: usize,
+    request_cmd: u8
+}
+
+impl Session {
+    pub fn new(username: &str, auth_type: u8, priv_level: PrivLevel, init_outbound_seq: u32)
+        -> Result<Session, Error>
+    {
+        let username = GetSessionChallengeRequest::new(auth_type, username)?.username;
+        Ok(Session {
+            state: SessionState::Unauthenticated,
+            auth_type,
+            priv_level,
+            username,
+            init_outbound_seq,
+            peer_addr: 0x20,
+            local_addr: 0x81,
+            rq_seq: 0,
+            request: [0; 22],
+            request_len: 0,
+            request_cmd: 0
+        })
+    }
+
+    /* session id and sequence number of the next packet to send */
+    pub fn packet_header(&mut self) -> (u32, u32) {
+        match &mut self.state {
+            SessionState::Unauthenticated | SessionState::ChannelCapsQueried => (0, 0),
+            SessionState::ChallengeReceived { tmp_session_id, .. } => (*tmp_session_id, 0),
+            SessionState::SessionActive { session_id, outbound_seq } => {
+                let seq = *outbound_seq;
+                /* 0 is only used outside of a session */
+                *outbound_seq = match seq.wrapping_add(1) { 0 => 1, n => n };
+                (*session_id, seq)
+            }
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, SessionState::SessionActive { .. })
+    }
+
+    fn request<T: BytesSerializable>(&mut self, cmd: u8, req: &T) -> Result<IpmiMessage<'_>, Error> {
+        let (request, len) = serialize_to_array::<_, 22>(req, true)?;
+        self.request = request;
+        self.request_len = len;
+        self.request_cmd = cmd;
+        self.rq_seq = (self.rq_seq + 1) & 0b00111111;
+
+        Ok(IpmiMessage {
+            peer_addr: self.peer_addr,
+            netfn: 0x06,
+            peer_lun: 0,
+            local_addr: self.local_addr,
+            seqnum: self.rq_seq,
+            local_lun: 0,
+            cmd,
+            data: IpmiData::Request(&self.request[..self.request_len])
+        })
+    }
+
+    /* the Get Channel Authentication Capabilities request opening the exchange */
+    pub fn start(&mut self) -> Result<IpmiMessage<'_>, Error> {
+        self.state = SessionState::Unauthenticated;
+        let req = GetChannelAuthCapRequest::new(ChannelNumber::CURRENT.into(), self.priv_level.as_u8(), false);
+        self.request(0x38, &req)
+    }
+
+    /*
+     * Advance with the response to the last request, return the next
+     * request or None once the session is established
+     */
+    pub fn handle(&mut self, msg: &IpmiMessage) -> Result<Option<IpmiMessage<'_>>, SessionError> {
+        if msg.netfn != 0x07 || msg.cmd != self.request_cmd || msg.seqnum != self.rq_seq {
+            return Err(SessionError::UnexpectedMessage);
+        }
+
+        let IpmiData::Response(code, _) = msg.data else {
+            return Err(SessionError::UnexpectedMessage);
+        };
+
+        if code != 0 {
+            return Err(SessionError::CompletionCode(code));
+        }
+
+        let command = decode_command(msg).ok_or(SessionError::Parse(Error::PayloadTooSmall))?;
+
+        match (self.state, command) {
+            (SessionState::Unauthenticated,
+             KnownCommand::GetChannelAuthCap(GetChannelAuthCap::Response(_, res))) => {
+                let supported = match self.auth_type {
+                    IPMI_AUTH_TYPE_NONE => res.supports_none(),
+                    IPMI_AUTH_TYPE_MD2  => res.supports_md2(),
+                    IPMI_AUTH_TYPE_MD5  => res.supports_md5(),
+                    IPMI_AUTH_TYPE_KEY  => res.supports_straight_password(),
+                    IPMI_AUTH_TYPE_OEM  => res.supports_oem_auth(),
+                    _ => false
+                };
+                if !supported {
+                    return Err(SessionError::AuthTypeUnsupported(self.auth_type));
+                }
+                self.state = SessionState::ChannelCapsQueried;
+                let req = GetSessionChallengeRequest { auth_type: self.auth_type, username: self.username };
+                Ok(Some(self.request(0x39, &req)?))
+            },
+            (SessionState::ChannelCapsQueried,
+             KnownCommand::GetSessionChallenge(GetSessionChallenge::Response(_, res))) => {
+                self.state = SessionState::ChallengeReceived {
+                    tmp_session_id: res.tmp_session_id,
+                    challenge: res.challenge_dat
+                };
+                let req = ActivateSessionRequest::from_challenge(
+                    self.auth_type, self.priv_level.as_u8(), &res, self.init_outbound_seq);
+                Ok(Some(self.request(0x3a, &req)?))
+            },
+            (SessionState::ChallengeReceived { .. },
+             KnownCommand::ActivateSession(ActivateSession::Response(_, res))) => {
+                self.state = SessionState::SessionActive {
+                    session_id: res.session_id,
+                    outbound_seq: res.init_inbound_seq
+                };
+                let req = SetSessionPrivLevelRequest { priv_level: self.priv_level };
+                Ok(Some(self.request(0x3b, &req)?))
+            },
+            (SessionState::SessionActive { .. },
+             KnownCommand::SetSessionPrivLevel(SetSessionPrivLevel::Response(_, _))) => Ok(None),
+            _ => Err(SessionError::UnexpectedMessage)
+        }
+    }
+}
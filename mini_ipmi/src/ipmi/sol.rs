@@ -0,0 +1,89 @@
+use macros::*;
+
+use crate::ipmi::summon_from_bytes;
+use crate::ipmi::{BytesDeserializable, BytesSerializationSized, BytesSerializable};
+use crate::ipmi::Error;
+
+/* RMCP+ payload type carrying SOL packets */
+pub const PAYLOAD_TYPE_SOL: u8 = 0x01;
+
+/* operation, from the remote console to the BMC */
+pub const SOL_OP_NACK:           u8 = 0b01000000;
+pub const SOL_OP_RING_WOR:       u8 = 0b00100000;
+pub const SOL_OP_GENERATE_BREAK: u8 = 0b00010000;
+pub const SOL_OP_CTS_PAUSE:      u8 = 0b00001000;
+pub const SOL_OP_DROP_DCD_DSR:   u8 = 0b00000100;
+pub const SOL_OP_FLUSH_INBOUND:  u8 = 0b00000010;
+pub const SOL_OP_FLUSH_OUTBOUND: u8 = 0b00000001;
+
+/* status, from the BMC to the remote console */
+pub const SOL_STATUS_NACK:             u8 = 0b01000000;
+pub const SOL_STATUS_TRANSFER_UNAVAIL: u8 = 0b00100000;
+pub const SOL_STATUS_DEACTIVATED:      u8 = 0b00010000;
+pub const SOL_STATUS_TX_OVERRUN:       u8 = 0b00001000;
+pub const SOL_STATUS_BREAK_DETECTED:   u8 = 0b00000100;
+
+/* 
+ * A SOL packet, the payload of a RMCP+ packet of payload type 0x01. A
+ * sequence number of 0 marks an ACK only packet without character data.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SolPayload<'a> {
+    pub packet_seq: u8,
+    pub ack_seq: u8,
+    pub accepted_chars: u8,
+    pub operation_status: u8,
+    pub data: &'a [u8]
+}
+
+impl SolPayload<'_> {
+    pub fn packet_seq_number(&self) -> u8 {
+        self.packet_seq & 0b00001111
+    }
+
+    pub fn ack_seq_number(&self) -> u8 {
+        self.ack_seq & 0b00001111
+    }
+
+    pub fn is_ack_only(&self) -> bool {
+        self.packet_seq_number() == 0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ActivateSolPayloadRequest {
+    pub payload_type: u8,
+    pub payload_instance: u8,
+    pub aux_data: [u8; 4]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct ActivateSolPayloadResponse {
+    pub aux_data: [u8; 4],
+
+    #[bytes_serialize(endian = "le")]
+    pub inbound_payload_size: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub outbound_payload_size: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub payload_udp_port: u16,
+
+    #[bytes_serialize(endian = "le")]
+    pub payload_vlan: u16
+}
+
+pub const SOL_PARAM_ENABLE:         u8 = 1;
+pub const SOL_PARAM_AUTHENTICATION: u8 = 2;
+pub const SOL_PARAM_BIT_RATE:       u8 = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesDefault, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetSolConfigParamRequest<'a> {
+    pub channel: u8,
+    pub parameter_selector: u8,
+    pub data: &'a [u8]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, BytesSerializationSized, BytesSerializable, BytesDeserializable)]
+pub struct SetSolConfigParamResponse {}
@@ -0,0 +1,90 @@
+use crate::ipmi::*;
+
+/* values below are seconds since the BMC initialized, not since 1970 */
+pub const IPMI_TIMESTAMP_PRE_INIT_MAX: u32 = 0x20000000;
+pub const IPMI_TIMESTAMP_UNSPECIFIED:  u32 = 0xffffffff;
+
+/* 
+ * Timestamp of SEL and SDR fields, seconds since 1970 in UTC, little endian
+ * on the wire
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct IpmiTimestamp(pub u32);
+
+impl IpmiTimestamp {
+    pub fn is_pre_init(&self) -> bool {
+        self.0 < IPMI_TIMESTAMP_PRE_INIT_MAX
+    }
+
+    pub fn is_unspecified(&self) -> bool {
+        self.0 == IPMI_TIMESTAMP_UNSPECIFIED
+    }
+
+    /* seconds since 1970, None if the timestamp is not wall clock time */
+    pub fn as_unix_secs(&self) -> Option<u32> {
+        if self.is_pre_init() || self.is_unspecified() {
+            None
+        } else {
+            Some(self.0)
+        }
+    }
+}
+
+impl From<u32> for IpmiTimestamp {
+    fn from(value: u32) -> IpmiTimestamp {
+        IpmiTimestamp(value)
+    }
+}
+
+impl From<IpmiTimestamp> for u32 {
+    fn from(value: IpmiTimestamp) -> u32 {
+        value.0
+    }
+}
+
+/* (year, month, day) of days since 1970-01-01 */
+fn civil_from_days(days: u32) -> (u32, u32, u32) {
+    /* shift the epoch to 0000-03-01, the day after a leap day */
+    let z   = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp  = (5 * doy + 2) / 153;
+    let day   = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year  = yoe + era * 400 + u32::from(month <= 2);
+    (year, month, day)
+}
+
+impl core::fmt::Display for IpmiTimestamp {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.as_unix_secs() {
+            Some(secs) => {
+                let (year, month, day) = civil_from_days(secs / 86400);
+                let time = secs % 86400;
+                write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                    year, month, day, time / 3600, time / 60 % 60, time % 60)
+            },
+            None if self.is_unspecified() => write!(f, "unspecified"),
+            None => write!(f, "{}s after initialization", self.0)
+        }
+    }
+}
+
+impl BytesSerializationSized for IpmiTimestamp {
+    fn size(&self) -> usize { 4 }
+}
+
+impl BytesSerializable for IpmiTimestamp {
+    fn write_to_slice(&self, slice: &mut [u8], strict: bool) -> Result<(), Error>
+    {
+        self.0.to_le_bytes().write_to_slice(slice, strict)
+    }
+}
+
+impl BytesDeserializable<'_> for IpmiTimestamp {
+    fn from_bytes(slice: &'_ [u8], strict: bool) -> Result<IpmiTimestamp, Error> {
+        <[u8; 4]>::from_bytes(slice, strict).map(|bytes| IpmiTimestamp(u32::from_le_bytes(bytes)))
+    }
+}
@@ -1,8 +1,15 @@
+/* 
+ * The take macros read at `$idx` and advance it, returning 
+ * `Error::PayloadTooSmall` from the enclosing function when `$slice` is too
+ * short, so they never panic on malformed input.
+ */
+
 #[macro_export]
 macro_rules! take {
     ($slice:expr,$idx:expr,$cnt:literal) => {
         {
-            let r = &$slice[$idx..($idx + $cnt)];
+            let r = $slice.get($idx..($idx + $cnt))
+                .ok_or($crate::ipmi::Error::PayloadTooSmall)?;
             $idx += $cnt;
             r
         }
@@ -13,7 +20,7 @@ macro_rules! take {
 macro_rules! take_u8 {
     ($slice:expr,$idx:expr) => {
         {
-            let r = $slice[$idx];
+            let r = *$slice.get($idx).ok_or($crate::ipmi::Error::PayloadTooSmall)?;
             $idx += 1;
             r
         }
@@ -21,22 +28,34 @@ macro_rules! take_u8 {
 }
 
 #[macro_export]
-macro_rules! take_be_u32 {
-    ($slice:expr,$idx:expr) => {
+macro_rules! take_array {
+    ($slice:expr,$idx:expr,$cnt:literal) => {
         {
-            let var = crate::take!($slice, $idx, 4);
-            u32::from_be_bytes(var.try_into().unwrap())
+            let var: [u8; $cnt] = $crate::take!($slice, $idx, $cnt).try_into()
+                .map_err(|_| $crate::ipmi::Error::PayloadTooSmall)?;
+            var
         }
     }
 }
 
+#[macro_export]
+macro_rules! take_be_u16 {
+    ($slice:expr,$idx:expr) => {
+        u16::from_be_bytes($crate::take_array!($slice, $idx, 2))
+    }
+}
+
+#[macro_export]
+macro_rules! take_be_u32 {
+    ($slice:expr,$idx:expr) => {
+        u32::from_be_bytes($crate::take_array!($slice, $idx, 4))
+    }
+}
+
 #[macro_export]
 macro_rules! take_le_u32 {
     ($slice:expr,$idx:expr) => {
-        {
-            let var = crate::take!($slice, $idx, 4);
-            u32::from_le_bytes(var.try_into().unwrap())
-        }
+        u32::from_le_bytes($crate::take_array!($slice, $idx, 4))
     }
 
 }
@@ -44,7 +63,22 @@ macro_rules! take_le_u32 {
 #[macro_export]
 macro_rules! take_remain {
     ($slice:expr,$idx:expr) => {
-        &$slice[$idx..]
+        $slice.get($idx..).ok_or($crate::ipmi::Error::PayloadTooSmall)?
     }
 }
 
+/* 
+ * Log why a decoder rejects its input with `defmt::warn!` when the `defmt`
+ * feature is enabled, expands to nothing otherwise.
+ */
+#[cfg(feature = "defmt")]
+macro_rules! parse_warn {
+    ($($arg:tt)*) => { defmt::warn!($($arg)*) }
+}
+
+#[cfg(not(feature = "defmt"))]
+macro_rules! parse_warn {
+    ($($arg:tt)*) => { }
+}
+
+pub(crate) use parse_warn;
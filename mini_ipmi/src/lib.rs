@@ -1,15 +1,33 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "client")]
+extern crate std;
+
 pub mod ipmi;
 
 #[cfg(test)]
 mod tests {
+    use macros::BytesSerializable;
     use super::ipmi::*;
     use super::ipmi::ipmi::*;
     use super::ipmi::cmd::*;
     use super::ipmi::ipmi::IpmiData;
-    use super::ipmi::asf::AsfMessage;
-    use super::ipmi::rmcp::{RmcpContent, RmcpMessage};
+    use super::ipmi::asf::{AsfData, AsfMessage};
+    use super::ipmi::rmcp::{MSG_CLASS_ACK, MSG_CLASS_IPMI, RmcpContent, RmcpFrames, RmcpKind, RmcpMessage, RmcpSeqCounter, OemLengthPrefix};
+    use super::ipmi::rsp::{Datagram, RspMessage, RSP_NEXT_HEADER_RMCP};
+    use super::ipmi::sdr::*;
+    use super::ipmi::sel::*;
+    use super::ipmi::session::*;
+    use super::ipmi::time::IpmiTimestamp;
+    use super::ipmi::lan::LanConfigClient;
+    use super::ipmi::completion::*;
+    use super::ipmi::pet::PetTrap;
+    use super::ipmi::sol::*;
+    use super::ipmi::oem::*;
+    use super::ipmi::rmcpp::*;
 
     #[test]
     fn test_asf_ping() {
@@ -19,12 +37,12 @@ mod tests {
         let mut out = [0u8;12];
         let decoded = RmcpMessage::from_bytes(&rmcp_asf_ping, true);
 
-        assert_eq!(decoded.is_ok(), true);
+        assert!(decoded.is_ok());
 
         let ping = decoded.unwrap();
         assert_eq!(reference, ping);
 
-        ping.write_to_slice(&mut out, true);
+        assert!(ping.write_to_slice(&mut out, true).is_ok());
         assert_eq!(rmcp_asf_ping, out);
     }
 
@@ -32,7 +50,7 @@ mod tests {
     fn test_ipmi_get_auth_capabilities_generic_req() {
         let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
         let decoded = RmcpMessage::from_bytes(&req_bytes, true);
-        assert_eq!(decoded.is_ok(), true);
+        assert!(decoded.is_ok());
         let unwrapped = decoded.unwrap();
         if let RmcpContent::Ipmi15(packet) = &unwrapped.data {
             if let Some(GetChannelAuthCap::Request(req)) = GetChannelAuthCap::from_message(&packet.data) {
@@ -45,6 +63,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ipmi15_packet_command_accessors() {
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        let decoded = RmcpMessage::from_bytes(&req_bytes, true).unwrap();
+
+        let RmcpContent::Ipmi15(packet) = &decoded.data else {
+            panic!("Should decode as IPMI 1.5 packet")
+        };
+        assert_eq!(packet.netfn(), 0x06);
+        assert_eq!(packet.cmd(), 0x38);
+        assert_eq!(packet.command_data(), &IpmiData::Request(&req_bytes[20..22]));
+    }
+
+    #[test]
+    fn test_ipmi_get_auth_capabilities_ipmi15_req() {
+        let req = GetChannelAuthCapRequest::from_bytes(&[0x0e, 0x04], true).unwrap();
+        assert_eq!(req.channel(), 14);
+        assert!(!req.requests_ipmi2());
+        assert_eq!(req, GetChannelAuthCapRequest::new(0x0e, IPMI_PRIV_LEVEL_ADMIN, false));
+    }
+
+    #[test]
+    fn test_ipmi_get_auth_capabilities_ipmi2_req() {
+        let req = GetChannelAuthCapRequest::new(0x0e, IPMI_PRIV_LEVEL_ADMIN, true);
+        assert_eq!(req.channel_number, 0x8e);
+        assert_eq!(req.channel(), 0x0e);
+        assert!(req.requests_ipmi2());
+
+        let mut out = [0u8; 2];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x8e, 0x04]);
+    }
+
     #[test]
     fn test_ipmi_get_auth_capabilities_req() {
         let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
@@ -52,7 +103,7 @@ mod tests {
 
         let decoded = RmcpMessage::from_bytes(&req_bytes, true);
 
-        assert_eq!(decoded.is_ok(), true);
+        assert!(decoded.is_ok());
 
         let unwrapped = decoded.unwrap();
 
@@ -82,6 +133,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ipmi_unauthenticated_packet() {
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+
+        let msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x81, seqnum: 0x01, local_lun: 0,
+            cmd: 0x38, data: IpmiData::Request(&[0x0e, 0x04])
+        };
+        let packet = Ipmi15Packet::unauthenticated(msg).unwrap();
+        assert_eq!(packet.payload_len, 9);
+
+        let mut out = [0u8; 23];
+        assert_eq!(RmcpMessage::from_ipmi15(packet, 0xff).write_into(&mut out, true), Ok(23));
+        assert_eq!(out, req_bytes);
+
+        let data = [0u8; IPMI_MAX_PAYLOAD];
+        let msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x81, seqnum: 0x01, local_lun: 0,
+            cmd: 0x38, data: IpmiData::Request(&data)
+        };
+        assert_eq!(Ipmi15Packet::unauthenticated(msg), Err(Error::PayloadTooLarge));
+    }
+
     #[test]
     fn test_ipmi_get_auth_capabilities_res() {
         let res_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x10,0x81,0x1c,0x63,0x20,0x04,0x38,0x00,0x01,0x04,0x14,0x00,0xd6,0xc1,0x00,0x00,0xf4];
@@ -90,7 +166,7 @@ mod tests {
 
         let decoded = RmcpMessage::from_bytes(&res_bytes, true);
 
-        assert_eq!(decoded.is_ok(), true);
+        assert!(decoded.is_ok());
 
         let unwrapped = decoded.unwrap();
 
@@ -101,7 +177,7 @@ mod tests {
             assert_eq!(packet.data.netfn, 0x07);
             assert_eq!(packet.data.cmd, 0x38);
 
-            if let IpmiData::Response(code, resd) = packet.data.data {
+            if let IpmiData::Response(_code, resd) = packet.data.data {
                 if let Ok(req) = GetChannelAuthCapResponse::from_bytes(resd, true) {
                     assert_eq!(req.channel_number, 1);
                 }
@@ -118,4 +194,2705 @@ mod tests {
             Err(y) => panic!("failed to write ipmi payload: {:?}", y)
         }
     }
+
+    #[test]
+    fn test_ipmi_get_auth_capabilities_res_accessors() {
+        let resd = [0x01, 0x04, 0x14, 0x00, 0xd6, 0xc1, 0x00, 0x00];
+        let res = GetChannelAuthCapResponse::from_bytes(&resd, true).unwrap();
+
+        assert!(res.supports_md5());
+        assert!(!res.supports_md2());
+        assert!(!res.supports_none());
+        assert!(!res.supports_straight_password());
+        assert!(!res.per_message_auth_enabled());
+        assert!(res.user_level_auth_enabled());
+        assert!(!res.kg_configured());
+        assert!(!res.supports_ipmi2());
+    }
+
+    #[test]
+    fn test_ipmi_get_auth_capabilities_builder() {
+        let res = GetChannelAuthCapResponse::builder(0x01)
+            .with_md5()
+            .with_straight_password()
+            .with_per_message_auth()
+            .with_non_null_usernames()
+            .with_ipmi2();
+
+        let mut out = [0u8; 8];
+        assert!(res.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x01, 0x94, 0x0c, 0x03, 0x00, 0x00, 0x00, 0x00]);
+
+        let res = GetChannelAuthCapResponse::from_bytes(&out, true).unwrap();
+        assert!(res.supports_md5() && res.supports_straight_password() && res.supports_ipmi2());
+        assert!(!res.supports_none() && !res.supports_md2() && !res.supports_oem_auth());
+        assert!(res.per_message_auth_enabled() && !res.user_level_auth_enabled());
+
+        let res = GetChannelAuthCapResponse::builder(0x01).with_oem_auth(IANA_SUPERMICRO, 0x42);
+        assert!(res.supports_oem_auth());
+        assert_eq!((res.manufacturer(), res.oem_aux), (IANA_SUPERMICRO, 0x42));
+    }
+
+    #[test]
+    fn test_rmcp_seq_counter() {
+        let mut counter = RmcpSeqCounter::starting_at(0xfd);
+        let mut fst = RmcpMessage::from_asf(AsfMessage::ping());
+        let mut snd = RmcpMessage::from_asf(AsfMessage::ping());
+
+        assert_eq!(counter.stamp(&mut fst), 0xfd);
+        assert_eq!(counter.stamp(&mut snd), 0xfe);
+        assert_eq!(fst.sequence_number, 0xfd);
+        assert_eq!(snd.sequence_number, 0xfe);
+
+        /* 0xff means "no ack", the counter must wrap around it */
+        assert_eq!(counter.stamp(&mut fst), 0x00);
+        assert_eq!(fst.sequence_number, 0x00);
+        assert_eq!(RmcpSeqCounter::starting_at(0xff).next_seq(), 0x00);
+    }
+
+    #[test]
+    fn test_rmcp_ack_round_trip() {
+        let ack = RmcpMessage::from_ack(0x05);
+        assert_eq!(ack.message_class & MSG_CLASS_ACK, MSG_CLASS_ACK);
+
+        let mut out = [0u8; 4];
+        assert!(ack.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x06, 0x00, 0x05, 0x86]);
+        assert_eq!(RmcpMessage::from_bytes(&out, true), Ok(ack));
+
+        /* the ACK bit survives a decode, so an ACK is re-encoded as an ACK */
+        let ipmi_ack = [0x06, 0x00, 0x05, 0x87];
+        let decoded = RmcpMessage::from_bytes(&ipmi_ack, true).unwrap();
+        assert_eq!(decoded.message_class, MSG_CLASS_IPMI | MSG_CLASS_ACK);
+        assert!(decoded.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, ipmi_ack);
+    }
+
+    #[test]
+    fn test_ipmi_sel_entry_res() {
+        let res_bytes = [
+            0x00, /* completion code */
+            0x05, 0x00, /* next record id */
+            0x04, 0x00, 0x02, 0x6f, 0x1a, 0x5e, 0x62, 0x20, 
+            0x00, 0x04, 0x10, 0x72, 0x6f, 0x02, 0xff, 0xff
+        ];
+        let data = IpmiData::Response(res_bytes[0], &res_bytes[1..]);
+
+        if let Some(GetSelEntry::Response(code, res)) = GetSelEntry::from_data(&data) {
+            assert_eq!(code, 0);
+            assert_eq!(res.next_record_id, 0x0005);
+            assert_eq!(res.record[0..2], [0x04, 0x00]);
+            assert_eq!(res.record[2], 0x02);
+
+            let mut out = [0u8; 18];
+            assert_eq!(res.size(), 18);
+            assert!(res.write_to_slice(&mut out, true).is_ok());
+            assert_eq!(out, res_bytes[1..]);
+        } else {
+            panic!("Should decode as GetSelEntry::Response")
+        }
+    }
+
+    #[test]
+    fn test_ipmi_sel_info_res() {
+        let res_bytes = [
+            0x51, 0x2a, 0x00, 0x10, 0x0e, 0x5e, 0x1a, 0x6f, 0x62,
+            0xff, 0xff, 0xff, 0xff, 0x02
+        ];
+        let res = GetSelInfoResponse::from_bytes(&res_bytes, true).unwrap();
+        assert_eq!(res.sel_version, 0x51);
+        assert_eq!(res.entries, 42);
+        assert_eq!(res.free_space, 0x0e10);
+        assert_eq!(res.most_recent_addition, IpmiTimestamp(0x626f1a5e));
+        assert!(res.most_recent_erase.is_unspecified());
+        assert_eq!(res.operation_support, 0x02);
+
+        let req = GetSelInfoRequest {};
+        assert_eq!(req.size(), 0);
+        assert_eq!(GetSelInfoRequest::from_bytes(&[], true), Ok(req));
+    }
+
+    #[test]
+    fn test_ipmi_reservations() {
+        let msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x0a, peer_lun: 0,
+            local_addr: 0x81, seqnum: 1, local_lun: 0,
+            cmd: 0x22, data: IpmiData::Request(&[])
+        };
+        assert_eq!(decode_command(&msg),
+            Some(KnownCommand::ReserveSdrRepository(ReserveSdrRepository::Request(ReserveSdrRepositoryRequest {}))));
+
+        let data = IpmiData::Response(0x00, &[0x34, 0x12]);
+        assert_eq!(ReserveSdrRepository::from_data(&data),
+            Some(ReserveSdrRepository::Response(0x00, ReserveSdrRepositoryResponse { reservation_id: 0x1234 })));
+        assert_eq!(ReserveSel::from_data(&data),
+            Some(ReserveSel::Response(0x00, ReserveSelResponse { reservation_id: 0x1234 })));
+    }
+
+    #[test]
+    fn test_ipmi_sdr_repository_info_res() {
+        let res_bytes = [
+            0x51, 0x40, 0x00, 0x00, 0x08, 0x5e, 0x1a, 0x6f, 0x62,
+            0x00, 0x00, 0x00, 0x00, 0x23
+        ];
+        let data = IpmiData::Response(0x00, &res_bytes);
+        let Some(GetSdrRepositoryInfo::Response(0x00, res)) = GetSdrRepositoryInfo::from_data(&data) else {
+            panic!("Should decode as GetSdrRepositoryInfo::Response")
+        };
+        assert_eq!(res.sdr_version, 0x51);
+        assert_eq!(res.record_count, 64);
+        assert_eq!(res.free_space, 0x0800);
+        assert_eq!(res.most_recent_addition, IpmiTimestamp(0x626f1a5e));
+        assert!(res.most_recent_erase.is_pre_init());
+        assert_eq!(res.operation_support, 0x23);
+
+        let res = GetSdrResponse::from_bytes(&[0xff, 0xff, 0x01, 0x00, 0x51], true).unwrap();
+        assert!(res.is_last());
+        assert_eq!(res.data, &[0x01, 0x00, 0x51]);
+        assert!(!GetSdrResponse { next_record_id: SDR_FIRST_RECORD, data: &[] }.is_last());
+    }
+
+    #[test]
+    fn test_ipmi_sel_time() {
+        /* Get SEL Time response to the remote console, completion code included */
+        let res_bytes = [0x81, 0x2c, 0x53, 0x20, 0x08, 0x48, 0x00, 0x5e, 0x1a, 0x6f, 0x62, 0x47];
+        let msg = IpmiMessage::from_bytes(&res_bytes, true).unwrap();
+
+        let Some(KnownCommand::GetSelTime(GetSelTime::Response(0x00, res))) = decode_command(&msg) else {
+            panic!("Should decode as GetSelTime::Response")
+        };
+        assert_eq!(res.timestamp, IpmiTimestamp(0x626f1a5e));
+
+        let req = SetSelTimeRequest { timestamp: res.timestamp };
+        let mut out = [0u8; 4];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, res_bytes[7..11]);
+        assert_eq!(SetSelTimeRequest::from_bytes(&out, true), Ok(req));
+    }
+
+    #[test]
+    fn test_ipmi_sel_clear_and_add() {
+        let req = ClearSelRequest::new(0x0102, SEL_CLEAR_INITIATE);
+        let mut out = [0u8; 6];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x02, 0x01, b'C', b'L', b'R', 0xaa]);
+        assert_eq!(ClearSelRequest::from_bytes(&out, true), Ok(req));
+
+        let data = IpmiData::Response(0x00, &[0x00]);
+        let Some(ClearSel::Response(0x00, res)) = ClearSel::from_data(&data) else {
+            panic!("Should decode as ClearSel::Response")
+        };
+        assert!(!res.is_completed());
+        assert!(ClearSelResponse { erasure_progress: SEL_ERASURE_COMPLETED }.is_completed());
+
+        let record = SelRecord { record_id: 0, record_type: SEL_RECORD_TYPE_SYSTEM_EVENT, data: [0x11; 13] };
+        let (record, _) = serialize_to_array::<_, 16>(&record, true).unwrap();
+        let req = AddSelEntryRequest { record };
+        assert_eq!(req.size(), 16);
+
+        let data = IpmiData::Response(0x00, &[0x34, 0x12]);
+        let Some(AddSelEntry::Response(0x00, res)) = AddSelEntry::from_data(&data) else {
+            panic!("Should decode as AddSelEntry::Response")
+        };
+        assert_eq!(res.record_id, 0x1234);
+    }
+
+    #[test]
+    fn test_ipmi_timestamp() {
+        extern crate std;
+        use std::string::ToString;
+
+        let stamp = IpmiTimestamp(0x626f1a5e);
+        assert!(!stamp.is_pre_init());
+        assert_eq!(stamp.as_unix_secs(), Some(1651448414));
+        assert_eq!(stamp.to_string(), "2022-05-01T23:40:14Z");
+        assert_eq!(IpmiTimestamp(951782400).to_string(), "2000-02-29T00:00:00Z");
+
+        let boot = IpmiTimestamp(90);
+        assert!(boot.is_pre_init());
+        assert_eq!(boot.as_unix_secs(), None);
+        assert_eq!(boot.to_string(), "90s after initialization");
+        assert_eq!(IpmiTimestamp(0xffffffff).to_string(), "unspecified");
+    }
+
+    #[test]
+    fn test_rsp_wrapped_ipmi() {
+        let rsp_bytes = [
+            /* RSP session id, sequence number */
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02,
+            /* RMCP header */
+            0x06, 0x00, 0xff, 0x07,
+            /* IPMI 1.5 GetChannelAuthCapabilities Request */
+            0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,
+            0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31,
+            /* pad, pad length, next header */
+            0x00, 0x00, 0x00, 0x03, 0x06,
+            /* integrity data */
+            0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7, 0xa8, 0xa9, 0xaa, 0xab, 0xac
+        ];
+        let mut out = [0u8; 48];
+
+        let rsp = RspMessage::from_bytes(&rsp_bytes, 12, true).unwrap();
+        assert_eq!(rsp.session_id, 1);
+        assert_eq!(rsp.seqnum, 2);
+        assert_eq!(rsp.payload, &rsp_bytes[8..31]);
+        assert_eq!(rsp.next_header, RSP_NEXT_HEADER_RMCP);
+        assert_eq!(rsp.integrity, &rsp_bytes[36..]);
+
+        let msg = rsp.message(true).unwrap();
+        assert_eq!(msg.classify(), RmcpKind::Ipmi15);
+        if let RmcpContent::Ipmi15(packet) = &msg.data {
+            assert_eq!(packet.data.netfn, 0x06);
+            assert_eq!(packet.data.cmd, 0x38);
+        } else {
+            panic!("Should decode as IPMI 1.5 packet")
+        }
+
+        let rebuilt = RspMessage::new(1, 2, rsp.payload, rsp.integrity);
+        assert_eq!(rebuilt, rsp);
+        assert_eq!(rebuilt.size(), rsp_bytes.len());
+        assert!(rebuilt.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, rsp_bytes);
+
+        let mut other_header = rsp_bytes;
+        other_header[35] = 0x07;
+        assert_eq!(RspMessage::from_bytes(&other_header, 12, true), Err(Error::UnsupportedProtocol));
+    }
+
+    #[test]
+    fn test_rsp_datagram() {
+        let rsp_bytes = [
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02,
+            0x06, 0x00, 0xff, 0x07,
+            0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,
+            0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31,
+            0x00, 0x00, 0x00, 0x03, 0x06,
+            0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7, 0xa8, 0xa9, 0xaa, 0xab, 0xac
+        ];
+        let rmcp_bytes = &rsp_bytes[8..31];
+
+        assert!(RspMessage::looks_like_rsp(&rsp_bytes, 12));
+        assert!(!RspMessage::looks_like_rsp(rmcp_bytes, 12));
+        assert!(!RspMessage::looks_like_rsp(&rsp_bytes, 20));
+
+        let Ok(Datagram::Rsp(rsp)) = Datagram::from_bytes(&rsp_bytes, 12, true) else {
+            panic!("Should decode as RSP")
+        };
+        assert_eq!(rsp.session_id, 1);
+
+        let datagram = Datagram::from_bytes(rmcp_bytes, 12, true).unwrap();
+        assert!(matches!(datagram, Datagram::Rmcp(_)));
+
+        let wrapped = Datagram::Rsp(rsp).into_message(true).unwrap();
+        assert_eq!(wrapped, datagram.into_message(true).unwrap());
+    }
+
+    #[test]
+    fn test_ipmi_read_fru_data() {
+        let req_bytes = [0x00, 0x08, 0x00, 0x04];
+        let res_bytes = [0x04, 0x01, 0x00, 0x00, 0x01];
+
+        let req = ReadFruDataRequest::from_bytes(&req_bytes, true).unwrap();
+        assert_eq!(req.fru_device_id, 0);
+        assert_eq!(req.offset, 8);
+        assert_eq!(req.count, 4);
+
+        let data = IpmiData::Response(0x00, &res_bytes);
+        if let Some(ReadFruData::Response(code, res)) = ReadFruData::from_data(&data) {
+            assert_eq!(code, 0);
+            assert_eq!(res.count_returned, 4);
+            assert_eq!(res.data, &res_bytes[1..]);
+
+            let mut out = [0u8; 5];
+            assert_eq!(res.size(), 5);
+            assert!(res.write_to_slice(&mut out, true).is_ok());
+            assert_eq!(out, res_bytes);
+        } else {
+            panic!("Should decode as ReadFruData::Response")
+        }
+
+        let info = GetFruInventoryAreaInfoResponse::from_bytes(&[0x00, 0x04, 0x01], true).unwrap();
+        assert_eq!(info.size, 0x400);
+        assert!(info.is_word_access());
+        assert!(!info.is_byte_access());
+    }
+
+    #[test]
+    fn test_sdr_convert_reading_fixed() {
+        let mut record_bytes = [
+            0x02, 0x00, 0x51, 0x01, 0x2f,   /* record header */
+            0x20, 0x00, 0x30, 0x07, 0x01, 0x7f, 0x68, 0x01, 0x01,
+            0x80, 0x0a, 0x80, 0x0a, 0x3f, 0x3f,
+            0x80, 0x01, 0x00,               /* 2's complement, degrees C */
+            0x00, 0x05, 0x00, 0x9c, 0xc0, 0x00, 0xf0,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0xc8,
+            b'C', b'P', b'U', b' ', b'T', b'e', b'm', b'p'
+        ];
+
+        let record = SdrFullRecord::from_bytes(&record_bytes, true).unwrap();
+        assert_eq!(record.record_type, SDR_RECORD_TYPE_FULL);
+        assert_eq!(record.analog_format(), SDR_ANALOG_FORMAT_TWOS_COMP);
+        assert_eq!(record.m(), 5);
+        assert_eq!(record.b(), -100);
+        assert_eq!(record.r_exp(), -1);
+        assert_eq!(record.b_exp(), 0);
+        assert_eq!(record.id_string, b"CPU Temp");
+
+        /* (5 * 70 - 100) * 10^-1 = 25.0 */
+        assert_eq!(record.convert_reading_fixed(70), (250, -1));
+        /* (5 * -10 - 100) * 10^-1 = -15.0 */
+        assert_eq!(record.convert_reading_fixed(0xf6), (-150, -1));
+
+        /* M = 1, B = 3, Bexp = -1, Rexp = 2: (x + 0.3) * 100 */
+        record_bytes[24] = 0x01;
+        record_bytes[26] = 0x03;
+        record_bytes[27] = 0x00;
+        record_bytes[29] = 0x2f;
+        let record = SdrFullRecord::from_bytes(&record_bytes, true).unwrap();
+        assert_eq!(record.convert_reading_fixed(7), (73, 1));
+
+        /* B * 10^Bexp beyond i32 drops precision rather than overflowing */
+        record_bytes[26] = 0xff;
+        record_bytes[27] = 0x40;
+        record_bytes[29] = 0x07;
+        let record = SdrFullRecord::from_bytes(&record_bytes, true).unwrap();
+        assert_eq!(record.b(), 511);
+        assert_eq!(record.convert_reading_fixed(0), (511000000, 1));
+    }
+
+    #[test]
+    fn test_sdr_reader_assembles_chunks() {
+        let mut record = [0u8; 64];
+        record[0..5].copy_from_slice(&[0x02, 0x00, 0x51, 0x01, 59]);
+        for (i, b) in record[5..].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        let mut calls = 0;
+        let mut reserved = 0;
+        let mut reader = SdrReader::new(0x0001, 16);
+        let mut out = [0u8; 128];
+
+        let result = reader.read_record(0x0002, &mut out, 
+            |req: &GetSdrRequest, buf: &mut [u8]| {
+                calls += 1;
+                /* the reservation is cancelled once in the middle of the read */
+                if calls == 3 {
+                    return Ok((0xc5, 0));
+                }
+                assert_eq!(req.record_id, 0x0002);
+                let offset = usize::from(req.offset);
+                let len = usize::from(req.bytes_to_read);
+                let res = GetSdrResponse { 
+                    next_record_id: 0x0003,
+                    data: &record[offset..][..len]
+                };
+                res.write_to_slice(buf, true).map(|_| (0u8, res.size()))
+            },
+            || { reserved += 1; Ok(0x0002) });
+
+        assert_eq!(result, Ok((0x0003, 64)));
+        assert_eq!(out[..64], record);
+        assert_eq!(reserved, 1);
+        assert_eq!(reader.reservation_id, 0x0002);
+        /* 2 reads before cancellation, 1 cancelled, 4 reads after */
+        assert_eq!(calls, 7);
+    }
+
+    #[test]
+    fn test_sel_iterator() {
+        static FIRST: [u8; 18] = [0x10, 0x00, 0x01, 0x00, 0x02, 0x78, 0x56, 0x34, 0x12, 0x20, 0x00, 0x04, 0x01, 0x30, 0x6f, 0x02, 0xff, 0xff];
+        static LAST:  [u8; 18] = [0xff, 0xff, 0x10, 0x00, 0x02, 0x79, 0x56, 0x34, 0x12, 0x20, 0x00, 0x04, 0x01, 0x30, 0x6f, 0x01, 0xff, 0xff];
+
+        fn response(code: u8, data: &[u8]) -> IpmiMessage<'_> {
+            IpmiMessage {
+                peer_addr: 0x81, netfn: 0x0b, peer_lun: 0,
+                local_addr: 0x20, seqnum: 0, local_lun: 0,
+                cmd: 0x43, data: IpmiData::Response(code, data)
+            }
+        }
+
+        let mut requested = [0u16; 2];
+        let mut calls = 0;
+
+        let iter = SelIterator::new(0x0001, |req: &IpmiMessage| {
+            assert_eq!((req.netfn, req.cmd, req.seqnum), (0x0a, 0x43, calls as u8));
+            let IpmiData::Request(data) = req.data else { panic!("not a request") };
+            let req = GetSelEntryRequest::from_bytes(data, true).unwrap();
+            assert_eq!(req.reservation_id, 0x0001);
+            requested[calls] = req.record_id;
+            calls += 1;
+
+            let data: &'static [u8] = if req.record_id == 0x0000 { &FIRST } else { &LAST };
+            Ok::<_, ()>(response(0, data))
+        });
+
+        let mut records = [0u16; 2];
+        let mut count = 0;
+        for record in iter {
+            let record = record.unwrap();
+            assert_eq!(record.record_type, SEL_RECORD_TYPE_SYSTEM_EVENT);
+            records[count] = record.record_id;
+            count += 1;
+        }
+
+        assert_eq!(count, 2);
+        assert_eq!(records, [0x0001, 0x0010]);
+        assert_eq!(requested, [0x0000, 0x0010]);
+
+        /* completion code and transport errors end the walk */
+        let mut iter = SelIterator::new(0x0001, |_: &IpmiMessage| Ok::<_, ()>(response(0xc5, &[])));
+        assert_eq!(iter.next(), Some(Err(CallError::CompletionCode(0xc5))));
+        assert_eq!(iter.next(), None);
+
+        let mut iter = SelIterator::new(0x0001, |_: &IpmiMessage| Err::<IpmiMessage, _>("timeout"));
+        assert_eq!(iter.next(), Some(Err(CallError::Transport("timeout"))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_activate_session_from_challenge() {
+        let challenge = [0x3c, 0x11, 0x00, 0x00,
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+            0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10];
+        let res = GetSessionChallengeResponse::from_bytes(&challenge, true).unwrap();
+        assert_eq!(res.tmp_session_id, 0x113c);
+
+        let req = ActivateSessionRequest::from_challenge(IPMI_AUTH_TYPE_MD5, 0x04, &res, 0x01);
+        let mut out = [0u8; 22];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out[..2], [IPMI_AUTH_TYPE_MD5, 0x04]);
+        assert_eq!(out[2..18], challenge[4..]);
+        assert_eq!(out[18..], [0x01, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_session_state_machine() {
+        fn response(req: (u8, u8), data: &[u8]) -> IpmiMessage<'_> {
+            IpmiMessage {
+                peer_addr: 0x81, netfn: 0x07, peer_lun: 0,
+                local_addr: 0x20, seqnum: req.1, local_lun: 0,
+                cmd: req.0, data: IpmiData::Response(0, data)
+            }
+        }
+
+        fn sent(msg: &IpmiMessage, data: &mut [u8; 22]) -> (u8, u8) {
+            let IpmiData::Request(bytes) = msg.data else { panic!("not a request") };
+            data[..bytes.len()].copy_from_slice(bytes);
+            (msg.cmd, msg.seqnum)
+        }
+
+        let mut data = [0u8; 22];
+        let mut session = Session::new("admin", IPMI_AUTH_TYPE_MD5, PrivLevel::Admin, 0x11223344).unwrap();
+        assert_eq!(session.packet_header(), (0, 0));
+
+        let req = sent(&session.start().unwrap(), &mut data);
+        assert_eq!((req.0, &data[..2]), (0x38, &[0x0e, 0x04][..]));
+
+        /* only MD5 offered */
+        let caps = [0x01, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let req = sent(&session.handle(&response(req, &caps)).unwrap().unwrap(), &mut data);
+        assert_eq!(session.state, SessionState::ChannelCapsQueried);
+        assert_eq!((req.0, &data[..6]), (0x39, &[0x02, b'a', b'd', b'm', b'i', b'n'][..]));
+
+        let challenge = [0x78, 0x56, 0x34, 0x12, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa,
+                         0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa];
+        let req = sent(&session.handle(&response(req, &challenge)).unwrap().unwrap(), &mut data);
+        assert_eq!(session.state, SessionState::ChallengeReceived { tmp_session_id: 0x12345678, challenge: [0xaa; 16] });
+        assert_eq!(session.packet_header(), (0x12345678, 0));
+        let activate = ActivateSessionRequest::from_bytes(&data, true).unwrap();
+        assert_eq!(activate.challenge_string, [0xaa; 16]);
+        assert_eq!(activate.init_outbound_seq, 0x11223344);
+
+        let activated = [0x02, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x04];
+        let req = sent(&session.handle(&response(req, &activated)).unwrap().unwrap(), &mut data);
+        assert!(session.is_active());
+        assert_eq!((req.0, data[0]), (0x3b, 0x04));
+        assert_eq!(session.packet_header(), (0x00000001, 0x10));
+        assert_eq!(session.packet_header(), (0x00000001, 0x11));
+
+        assert_eq!(session.handle(&response(req, &[0x04])), Ok(None));
+
+        /* a response to some other request */
+        assert_eq!(session.handle(&response((0x38, req.1), &caps)), Err(SessionError::UnexpectedMessage));
+
+        let mut session = Session::new("admin", IPMI_AUTH_TYPE_MD2, PrivLevel::Admin, 1).unwrap();
+        let req = sent(&session.start().unwrap(), &mut data);
+        assert_eq!(session.handle(&response(req, &caps)),
+                   Err(SessionError::AuthTypeUnsupported(IPMI_AUTH_TYPE_MD2)));
+    }
+
+    #[test]
+    fn test_ipmi_acpi_power_state() {
+        let req = SetAcpiPowerStateRequest {
+            system_power_state: ACPI_STATE_SET | ACPI_SYSTEM_S5_G2,
+            device_power_state: ACPI_STATE_NO_CHANGE
+        };
+        let mut out = [0u8; 2];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x85, 0x7f]);
+
+        let data = IpmiData::Response(0x00, &[0x00, 0x03]);
+        let Some(GetAcpiPowerState::Response(0x00, res)) = GetAcpiPowerState::from_data(&data) else {
+            panic!("Should decode as GetAcpiPowerState::Response")
+        };
+        assert_eq!(res.system_state(), ACPI_SYSTEM_S0_G0);
+        assert_eq!(res.device_state(), ACPI_DEVICE_D3);
+    }
+
+    #[test]
+    fn test_ipmi_boot_options() {
+        let flags = boot_flags(BOOT_DEVICE_PXE, false, false);
+        assert_eq!(flags, [0x80, 0x04, 0x00, 0x00, 0x00]);
+
+        let req = SetSystemBootOptionsRequest::boot_flags(&flags);
+        let mut out = [0u8; 6];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x05, 0x80, 0x04, 0x00, 0x00, 0x00]);
+
+        let data = IpmiData::Response(0x00, &[0x01, 0x05, 0xa0, 0x08, 0x00, 0x00, 0x00]);
+        let Some(GetSystemBootOptions::Response(0x00, res)) = GetSystemBootOptions::from_data(&data) else {
+            panic!("Should decode as GetSystemBootOptions::Response")
+        };
+        assert_eq!(res.parameter_selector(), BOOT_PARAM_BOOT_FLAGS);
+        assert!(res.is_valid());
+        assert_eq!(res.data, &boot_flags(BOOT_DEVICE_DISK, false, true));
+    }
+
+    #[test]
+    fn test_ipmi_health_commands() {
+        let data = IpmiData::Response(0x00, &[0x3c, 0x10, 0x27, 0x00, 0x00]);
+        let Some(GetPohCounter::Response(0x00, poh)) = GetPohCounter::from_data(&data) else {
+            panic!("Should decode as GetPohCounter::Response")
+        };
+        assert_eq!(poh.counter_reading, 10000);
+        assert_eq!(poh.minutes(), 600000);
+
+        let res = GetSelfTestResultsResponse::from_bytes(&[0x55, 0x00], true).unwrap();
+        assert!(res.passed());
+        let res = GetSelfTestResultsResponse::from_bytes(&[0x56, 0x00], true).unwrap();
+        assert!(res.not_implemented() && !res.passed());
+        let res = GetSelfTestResultsResponse::from_bytes(&[0x57, 0x08], true).unwrap();
+        assert_eq!((res.result, res.detail), (SELF_TEST_CORRUPTED, 0x08));
+    }
+
+    #[test]
+    fn test_ipmi_close_session() {
+        let close_bytes = [
+            0x06, 0x00, 0xff, 0x07,
+            0x00, 0x03, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12, 0x0b,
+            0x20, 0x18, 0xc8, 0x81, 0x08, 0x3c, 0x78, 0x56, 0x34, 0x12, 0x27
+        ];
+
+        let decoded = RmcpMessage::from_bytes(&close_bytes, true).unwrap();
+        let RmcpContent::Ipmi15(packet) = &decoded.data else {
+            panic!("Should decode as IPMI 1.5 packet")
+        };
+        let req = CloseSessionRequest { session_id: 0x12345678, session_handle: None };
+        assert_eq!(decode_command(&packet.data), Some(KnownCommand::CloseSession(CloseSession::Request(req))));
+
+        let mut out = [0u8; 25];
+        assert_eq!(decoded.write_into(&mut out, true), Ok(25));
+        assert_eq!(out, close_bytes);
+
+        let by_handle = CloseSessionRequest::from_bytes(&[0x00, 0x00, 0x00, 0x00, 0x02], true).unwrap();
+        assert_eq!(by_handle.session_handle, Some(0x02));
+    }
+
+    #[test]
+    fn test_ipmi_get_device_id() {
+        let res_bytes = [
+            0x00, 0x20, 0x01, 0x03, 0x28, 0x02, 0xbf,
+            0x7c, 0x2a, 0x00, 0x46, 0x08, 0x00, 0x00, 0x00, 0x00
+        ];
+        let data = IpmiData::Response(res_bytes[0], &res_bytes[1..]);
+        let Some(GetDeviceId::Response(0x00, res)) = GetDeviceId::from_data(&data) else {
+            panic!("Should decode as GetDeviceId::Response")
+        };
+
+        assert_eq!((res.device_id, res.device_revision), (0x20, 0x01));
+        assert!(res.device_available());
+        assert_eq!(res.manufacturer(), 10876);
+        assert_eq!(res.product_id, 0x0846);
+        assert_eq!(res.aux_firmware_rev, &[0x00; 4]);
+
+        assert!(res.supports_sensor() && res.supports_sdr_repository() && res.supports_sel());
+        assert!(res.supports_fru_inventory() && res.supports_chassis());
+        assert!(res.supports_ipmb_event_receiver() && res.supports_ipmb_event_generator());
+        assert!(!res.supports_bridge());
+
+        /* without the auxiliary firmware revision */
+        let res = GetDeviceIdResponse::from_bytes(&res_bytes[1..12], true).unwrap();
+        assert!(res.aux_firmware_rev.is_empty());
+    }
+
+    #[test]
+    fn test_ipmi_bmc_reset() {
+        assert_eq!(ColdResetRequest {}.size(), 0);
+        assert_eq!(WarmResetRequest {}.size(), 0);
+
+        let msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x81, seqnum: 1, local_lun: 0,
+            cmd: 0x03, data: IpmiData::Request(&[])
+        };
+        assert_eq!(decode_command(&msg), Some(KnownCommand::WarmReset(WarmReset::Request(WarmResetRequest {}))));
+
+        let data = IpmiData::Response(0x00, &[]);
+        assert_eq!(ColdReset::from_data(&data), Some(ColdReset::Response(0x00, ColdResetResponse {})));
+    }
+
+    #[test]
+    fn test_ipmi_chassis_capabilities() {
+        let data = IpmiData::Response(0x00, &[0x09, 0x20, 0x20, 0x20, 0x20]);
+        let Some(GetChassisCapabilities::Response(0x00, res)) = GetChassisCapabilities::from_data(&data) else {
+            panic!("not a chassis capabilities response");
+        };
+        assert!(res.provides_intrusion_sensor() && res.provides_power_interlock());
+        assert_eq!((res.sel_device_address, res.bridge_device_address), (0x20, None));
+        assert_eq!(res.size(), 5);
+
+        let res = GetChassisCapabilitiesResponse::from_bytes(&[0x00, 0x20, 0x20, 0x20, 0x20, 0x22], true).unwrap();
+        assert!(!res.provides_intrusion_sensor());
+        assert_eq!(res.bridge_device_address, Some(0x22));
+
+        let mut out = [0u8; 6];
+        assert!(res.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x00, 0x20, 0x20, 0x20, 0x20, 0x22]);
+
+        assert_eq!(GetChassisCapabilitiesResponse::from_bytes(&[0x00, 0x20, 0x20, 0x20], true),
+            Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_ipmi_watchdog_timer() {
+        let req = SetWatchdogTimerRequest {
+            timer_use: 0x04,
+            timer_actions: WATCHDOG_ACTION_POWER_CYCLE | WATCHDOG_PRE_TIMEOUT_NONE,
+            pre_timeout_interval: 0,
+            timer_use_expiration_flags: 0x10,
+            initial_countdown: 600
+        };
+        let mut out = [0u8; 6];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x04, 0x03, 0x00, 0x10, 0x58, 0x02]);
+
+        let res_bytes = [0x44, 0x03, 0x00, 0x00, 0x58, 0x02, 0x2c, 0x01];
+        let data = IpmiData::Response(0x00, &res_bytes);
+        if let Some(GetWatchdogTimer::Response(_, res)) = GetWatchdogTimer::from_data(&data) {
+            assert!(res.is_running());
+            assert_eq!(res.timeout_action(), WATCHDOG_ACTION_POWER_CYCLE);
+            assert_eq!(res.initial_countdown, 600);
+            assert_eq!(res.present_countdown, 300);
+        } else {
+            panic!("Should decode as GetWatchdogTimer::Response")
+        }
+
+        let empty = IpmiData::Response(0x00, &[]);
+        assert_eq!(ResetWatchdogTimer::from_data(&empty), 
+            Some(ResetWatchdogTimer::Response(0x00, ResetWatchdogTimerResponse {})));
+    }
+
+    #[test]
+    fn test_ipmi_send_message_wrap() {
+        let inner = IpmiMessage {
+            peer_addr: 0x72, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x20, seqnum: 0x01, local_lun: 0,
+            cmd: 0x01, data: IpmiData::Request(&[])
+        };
+
+        let mut inner_buf = [0u8; 32];
+        let req = SendMessageRequest::wrap(
+            SEND_MSG_TRACKING_REQUEST | 0x07, &inner, &mut inner_buf).unwrap();
+
+        assert_eq!(req.channel_number(), 0x07);
+        assert_eq!(req.tracking(), SEND_MSG_TRACKING_REQUEST);
+        assert_eq!(req.message, &[0x72, 0x18, 0x76, 0x20, 0x04, 0x01, 0xdb]);
+        assert_eq!(req.inner(true).as_ref(), Ok(&inner));
+
+        let mut out = [0u8; 8];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x47, 0x72, 0x18, 0x76, 0x20, 0x04, 0x01, 0xdb]);
+
+        let data = IpmiData::Request(&out);
+        if let Some(SendMessage::Request(decoded)) = SendMessage::from_data(&data) {
+            assert_eq!(decoded, req);
+        } else {
+            panic!("Should decode as SendMessage::Request")
+        }
+
+        let mut small = [0u8; 4];
+        assert_eq!(SendMessageRequest::wrap(0x07, &inner, &mut small), 
+            Err(Error::OutBufferTooSmall));
+    }
+
+    #[test]
+    fn test_ipmi_message_queue() {
+        let data = IpmiData::Response(0x00, &[0x03]);
+        if let Some(GetMessageFlags::Response(0x00, res)) = GetMessageFlags::from_data(&data) {
+            assert!(res.receive_queue_available());
+            assert!(res.event_buffer_full());
+            assert!(!res.watchdog_pretimeout());
+        } else {
+            panic!("Should decode as GetMessageFlags::Response")
+        }
+
+        let req = ClearMessageFlagsRequest { flags: MSG_FLAG_RECEIVE_QUEUE };
+        let mut out = [0u8; 1];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x01]);
+
+        /* a Get Device ID request received on channel 7 at admin level */
+        let bytes = [0x47, 0x20, 0x18, 0xc8, 0x81, 0x04, 0x01, 0x7a];
+        let msg = IpmiMessage {
+            peer_addr: 0x81, netfn: 0x07, peer_lun: 0,
+            local_addr: 0x20, seqnum: 0x01, local_lun: 0,
+            cmd: 0x33, data: IpmiData::Response(0x00, &bytes)
+        };
+
+        match decode_command(&msg) {
+            Some(KnownCommand::GetMessage(GetMessage::Response(0x00, res))) => {
+                assert_eq!(res.channel_number(), 0x07);
+                assert_eq!(res.privilege_level(), IPMI_PRIV_LEVEL_ADMIN);
+                assert_eq!(res.data, &bytes[1..]);
+            },
+            other => panic!("Should decode as GetMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_ipmi_master_write_read() {
+        /* read 2 bytes from register 0x00 of an EEPROM on private bus 2 */
+        let req = MasterWriteReadRequest {
+            channel: MasterWriteReadRequest::channel_byte(0, 2, I2C_BUS_PRIVATE),
+            slave_addr: 0xa0,
+            read_count: 2,
+            data: &[0x00]
+        };
+        assert_eq!(req.channel, 0x05);
+        assert_eq!((req.channel_number(), req.bus_id(), req.bus_type()), (0, 2, I2C_BUS_PRIVATE));
+        assert_eq!(MasterWriteReadRequest::channel_byte(0x1f, 0, 0), 0xf0);
+
+        let mut out = [0u8; 4];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x05, 0xa0, 0x02, 0x00]);
+
+        let data = IpmiData::Request(&out);
+        assert_eq!(MasterWriteRead::from_data(&data), Some(MasterWriteRead::Request(req)));
+
+        let data = IpmiData::Response(0x00, &[0x12, 0x34]);
+        let Some(MasterWriteRead::Response(0x00, res)) = MasterWriteRead::from_data(&data) else {
+            panic!("Should decode as MasterWriteRead::Response")
+        };
+        assert_eq!(res.data, &[0x12, 0x34]);
+    }
+
+    #[test]
+    fn test_rmcp_oem_batched_ipmi() {
+        let oem_bytes = [
+            0x06, 0x00, 0xff, 0x08,
+            0x00, 0x00, 0x11, 0xbe,
+            /* GetChannelAuthCapabilities Request */
+            0x09, 0x20, 0x18, 0xc8, 0x81, 0x04, 0x38, 0x0e, 0x04, 0x31,
+            /* GetDeviceId Request */
+            0x07, 0x20, 0x18, 0xc8, 0x81, 0x08, 0x01, 0x76
+        ];
+
+        let decoded = RmcpMessage::from_bytes(&oem_bytes, true).unwrap();
+        let mut iter = decoded.data.oem_ipmi_messages(OemLengthPrefix::U8, true).unwrap();
+
+        let fst = iter.next().unwrap().unwrap();
+        assert_eq!(fst.cmd, 0x38);
+        assert!(matches!(GetChannelAuthCap::from_message(&fst), 
+            Some(GetChannelAuthCap::Request(_))));
+
+        let snd = iter.next().unwrap().unwrap();
+        assert_eq!(snd.netfn, 0x06);
+        assert_eq!(snd.cmd, 0x01);
+        assert_eq!(snd.seqnum, 0x02);
+
+        assert!(iter.next().is_none());
+
+        /* a length running past the payload is reported once */
+        let truncated = RmcpContent::Oem { iana: 4542, data: &oem_bytes[8..20] };
+        let mut iter = truncated.oem_ipmi_messages(OemLengthPrefix::U8, true).unwrap();
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(iter.next(), Some(Err(Error::PayloadTooSmall)));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_strict_lenient() {
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        let bad_version = [0x07, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+
+        assert_eq!(RmcpMessage::from_bytes_strict(&req_bytes), 
+            RmcpMessage::from_bytes(&req_bytes, true));
+        assert_eq!(RmcpMessage::from_bytes_lenient(&req_bytes), 
+            RmcpMessage::from_bytes(&req_bytes, false));
+
+        assert_eq!(RmcpMessage::from_bytes_strict(&bad_version), 
+            Err(Error::InvalidRmcpVersionNumber(0x07)));
+        assert_eq!(RmcpMessage::from_bytes_lenient(&bad_version), 
+            RmcpMessage::from_bytes(&bad_version, false));
+        assert!(RmcpMessage::from_bytes_lenient(&bad_version).is_ok());
+    }
+
+    #[test]
+    fn test_asf_other_data_len() {
+        /* a reset message claiming 3 data bytes but carrying 2 */
+        let reset = [0x00, 0x00, 0x11, 0xbe, 0x10, 0x01, 0x00, 0x03, 0xaa, 0xbb];
+        assert_eq!(AsfMessage::from_bytes(&reset, true),
+            Err(Error::ExpectedSizeMismatch { expected: 11, actual: 10 }));
+
+        let asf = AsfMessage::from_bytes(&reset, false).unwrap();
+        assert_eq!((asf.data_len, asf.data), (0x03, AsfData::Other(&[0xaa, 0xbb])));
+
+        let mut valid = reset;
+        valid[7] = 0x02;
+        assert!(AsfMessage::from_bytes(&valid, true).is_ok());
+    }
+
+    #[test]
+    fn test_asf_max_payload() {
+        let data = [0u8; IPMI_MAX_PAYLOAD + 1];
+        let mut out = [0u8; 8 + IPMI_MAX_PAYLOAD + 1];
+
+        let asf = AsfMessage { iana: 4542, msg_type: 0x10, msg_tag: 0, data_len: 0, data: AsfData::Other(&data) };
+        assert_eq!(asf.write_to_slice(&mut out, true), Err(Error::InvalidConfiguration));
+
+        let asf = AsfMessage { data_len: 0xff, data: AsfData::Other(&data[..IPMI_MAX_PAYLOAD]), ..asf };
+        assert!(asf.write_to_slice(&mut out, true).is_ok());
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let mut variants = 0;
+
+        for code in 0..=u8::MAX {
+            let Some(error) = Error::from_code(code) else { continue };
+            assert_eq!(error.as_code(), code);
+
+            /* a new variant fails to compile here until it gets a code */
+            match error {
+                Error::OutBufferTooSmall | Error::InvalidConfiguration | Error::PayloadTooLarge
+                | Error::PayloadTooSmall | Error::UnsupportedProtocol | Error::InvalidChecksum => {},
+                Error::InvalidRmcpVersionNumber(value) | Error::InvalidRmcpReservedByte(value)
+                | Error::UndefinedAuthType(value) => assert_eq!(value, 0),
+                Error::ExpectedSizeMismatch { expected, actual } => assert_eq!((expected, actual), (0, 0))
+            }
+            variants += 1;
+        }
+
+        assert_eq!(variants, 10);
+        assert_eq!(Error::from_code(0x00), None);
+        assert_eq!(Error::ExpectedSizeMismatch { expected: 10, actual: 8 }.as_code(), 0x08);
+    }
+
+    #[test]
+    fn test_rmcp_header_error_precedence() {
+        let ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00];
+
+        let mut bad_version = ping;
+        bad_version[0] = 0x07;
+        assert_eq!(RmcpMessage::from_bytes(&bad_version, true), Err(Error::InvalidRmcpVersionNumber(0x07)));
+
+        let mut bad_reserved = ping;
+        bad_reserved[1] = 0x01;
+        assert_eq!(RmcpMessage::from_bytes(&bad_reserved, true), Err(Error::InvalidRmcpReservedByte(0x01)));
+
+        /* the version wins */
+        let mut both_bad = bad_version;
+        both_bad[1] = 0x01;
+        assert_eq!(RmcpMessage::from_bytes(&both_bad, true), Err(Error::InvalidRmcpVersionNumber(0x07)));
+        assert!(RmcpMessage::from_bytes(&both_bad, false).is_ok());
+    }
+
+    #[test]
+    fn test_channel_number() {
+        assert_eq!(ChannelNumber::CURRENT.0, 0x0e);
+        assert_eq!(u8::from(ChannelNumber::SYSTEM_INTERFACE), 0x0f);
+        assert!(ChannelNumber::from(0x0b).is_valid());
+        assert!(!ChannelNumber(0x0c).is_valid());
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesSerializationSized, BytesSerializable, macros::BytesDeserializable)]
+    struct WithChannel {
+        channel: ChannelNumber,
+        priv_level: u8
+    }
+
+    #[test]
+    fn test_derived_channel_number() {
+        let value = WithChannel { channel: ChannelNumber::CURRENT, priv_level: IPMI_PRIV_LEVEL_ADMIN };
+        assert_eq!(value.size(), 2);
+
+        let mut out = [0u8; 2];
+        assert!(value.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x0e, 0x04]);
+        assert_eq!(WithChannel::from_bytes(&out, true), Ok(value));
+        assert_eq!(WithChannel::from_bytes(&[], true), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_decode_command() {
+        let req_bytes = [0x20, 0x18, 0xc8, 0x81, 0x04, 0x38, 0x0e, 0x04, 0x31];
+        let msg = IpmiMessage::from_bytes(&req_bytes, true).unwrap();
+
+        match decode_command(&msg) {
+            Some(KnownCommand::GetChannelAuthCap(GetChannelAuthCap::Request(req))) =>
+                assert_eq!(ChannelNumber::from(req.channel_number), ChannelNumber::CURRENT),
+            other => panic!("Should decode as GetChannelAuthCap, got {:?}", other)
+        }
+
+        let fru_bytes = [0x81, 0x2c, 0x53, 0x20, 0x04, 0x11, 0x00, 0x01, 0xaa, 0x20];
+        let msg = IpmiMessage::from_bytes(&fru_bytes, true).unwrap();
+
+        match decode_command(&msg) {
+            Some(KnownCommand::ReadFruData(ReadFruData::Response(0, res))) =>
+                assert_eq!(res.data, &[0xaa]),
+            other => panic!("Should decode as ReadFruData, got {:?}", other)
+        }
+
+        let device_id = [0x20, 0x18, 0xc8, 0x81, 0x08, 0x01, 0x76];
+        let msg = IpmiMessage::from_bytes(&device_id, true).unwrap();
+        assert_eq!(decode_command(&msg), Some(KnownCommand::GetDeviceId(GetDeviceId::Request(GetDeviceIdRequest {}))));
+
+        /* Manufacturing Test On is not a known command */
+        let unknown = [0x20, 0x18, 0xc8, 0x81, 0x08, 0x05, 0x72];
+        let msg = IpmiMessage::from_bytes(&unknown, true).unwrap();
+        assert_eq!(decode_command(&msg), None);
+    }
+
+    #[test]
+    fn test_derived_write_matches_size() {
+        let res = GetWatchdogTimerResponse {
+            timer_use: 0x44, timer_actions: 0x03, pre_timeout_interval: 0,
+            timer_use_expiration_flags: 0, initial_countdown: 600, present_countdown: 300
+        };
+        /* oversized on purpose, only `size()` bytes are written */
+        let mut out = [0u8; 32];
+        assert!(res.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out[..8], [0x44, 0x03, 0x00, 0x00, 0x58, 0x02, 0x2c, 0x01]);
+    }
+
+    #[derive(BytesSerializable)]
+    struct InconsistentSize {
+        a: u8,
+        b: [u8; 2]
+    }
+
+    impl BytesSerializationSized for InconsistentSize {
+        fn size(&self) -> usize { 4 }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "disagree with its size()")]
+    fn test_derived_write_detects_size_drift() {
+        let value = InconsistentSize { a: 1, b: [2, 3] };
+        let mut out = [0u8; 4];
+        let _ = value.write_to_slice(&mut out, true);
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesDefault, macros::BytesSerializationSized, BytesSerializable, macros::BytesDeserializable)]
+    #[bytes_serialize(size = 4)]
+    struct WithReserved {
+        a: u8,
+        #[bytes_serialize(reserved = 0x5a)]
+        reserved: u8,
+        #[bytes_serialize(endian = "le")]
+        b: u16
+    }
+
+    #[test]
+    fn test_derived_reserved_byte() {
+        let value = WithReserved { a: 1, reserved: 0xff, b: 0x0302 };
+        assert_eq!(value.size(), 4);
+
+        /* the constant is written whatever the field holds */
+        let mut out = [0u8; 4];
+        assert!(value.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x01, 0x5a, 0x02, 0x03]);
+        assert_eq!(WithReserved::default().reserved, 0x5a);
+
+        let decoded = WithReserved::from_bytes(&out, true).unwrap();
+        assert_eq!(decoded, WithReserved { reserved: 0x5a, ..value });
+
+        let bad = [0x01, 0x00, 0x02, 0x03];
+        assert_eq!(WithReserved::from_bytes(&bad, true), Err(Error::InvalidConfiguration));
+        assert_eq!(WithReserved::from_bytes(&bad, false), Ok(decoded));
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesSerializationSized, BytesSerializable, macros::BytesDeserializable)]
+    #[bytes_serialize(endian = "be")]
+    struct BigEndianDefault {
+        iana: u32,
+        tag: u8,
+        #[bytes_serialize(endian = "le")]
+        length: u16
+    }
+
+    #[test]
+    fn test_derived_container_endian() {
+        let value = BigEndianDefault { iana: 4542, tag: 0x01, length: 0x0010 };
+        assert_eq!(value.size(), 7);
+
+        let mut out = [0u8; 7];
+        assert!(value.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x00, 0x00, 0x11, 0xbe, 0x01, 0x10, 0x00]);
+        assert_eq!(BigEndianDefault::from_bytes(&out, true), Ok(value));
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesSerializationSized, BytesSerializable, macros::BytesDeserializable)]
+    struct SignedFields {
+        offset: i8,
+        #[bytes_serialize(endian = "le")]
+        delta_le: i16,
+        #[bytes_serialize(endian = "be")]
+        delta_be: i16,
+        #[bytes_serialize(endian = "le")]
+        reading: i32
+    }
+
+    #[test]
+    fn test_derived_signed_integers() {
+        let value = SignedFields { offset: -2, delta_le: -40, delta_be: -40, reading: -1000 };
+        assert_eq!(value.size(), 9);
+
+        let mut out = [0u8; 9];
+        assert!(value.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0xfe, 0xd8, 0xff, 0xff, 0xd8, 0x18, 0xfc, 0xff, 0xff]);
+        assert_eq!(SignedFields::from_bytes(&out, true), Ok(value));
+    }
+
+    /* claims more bytes than it read */
+    #[derive(Debug, PartialEq)]
+    struct Overreported(u8);
+
+    impl BytesSerializationSized for Overreported {
+        fn size(&self) -> usize { 8 }
+    }
+
+    impl BytesDeserializable<'_> for Overreported {
+        fn from_bytes(slice: &[u8], strict: bool) -> Result<Overreported, Error> {
+            u8::from_bytes(slice, strict).map(Overreported)
+        }
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesSerializationSized, macros::BytesDeserializable)]
+    struct TrailingArray {
+        head: Overreported,
+        tail: [u8; 16]
+    }
+
+    #[test]
+    fn test_derived_truncated_input() {
+        let bytes = [0u8; 10];
+        assert_eq!(TrailingArray::from_bytes(&bytes, true), Err(Error::PayloadTooSmall));
+        assert_eq!(TrailingArray::from_bytes(&bytes[..4], false), Err(Error::PayloadTooSmall));
+        assert_eq!(GetSessionChallengeResponse::from_bytes(&bytes, true), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_derived_truncated_integer() {
+        /* cut in the middle of the little endian u16 and u32 fields */
+        let res = [0x44, 0x03, 0x00, 0x00, 0x58, 0x02, 0x2c, 0x01];
+        assert_eq!(GetWatchdogTimerResponse::from_bytes(&res[..5], true), Err(Error::PayloadTooSmall));
+        assert_eq!(GetWatchdogTimerResponse::from_bytes(&res[..7], false), Err(Error::PayloadTooSmall));
+        assert_eq!(GetPohCounterResponse::from_bytes(&[0x3c, 0x10, 0x27, 0x00], true), Err(Error::PayloadTooSmall));
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesDefault, macros::BytesSerializationSized, BytesSerializable, macros::BytesDeserializable)]
+    struct LenPrefixed<'a> {
+        tag: u8,
+        #[bytes_serialize(len_prefix = "u8")]
+        name: &'a [u8],
+        #[bytes_serialize(endian = "le")]
+        id: u16
+    }
+
+    #[test]
+    fn test_derived_len_prefix() {
+        let value = LenPrefixed { tag: 0x01, name: b"root", id: 0x0302 };
+        assert_eq!(value.size(), 8);
+
+        let mut out = [0u8; 8];
+        assert!(value.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x01, 0x04, b'r', b'o', b'o', b't', 0x02, 0x03]);
+        assert_eq!(LenPrefixed::from_bytes(&out, true), Ok(value));
+        assert_eq!(LenPrefixed::default().size(), 4);
+
+        /* length running past the buffer */
+        let bad = [0x01, 0x09, b'r', b'o', b'o', b't', 0x02, 0x03];
+        assert_eq!(LenPrefixed::from_bytes(&bad, true), Err(Error::PayloadTooSmall));
+
+        let long = [0u8; 256];
+        let value = LenPrefixed { tag: 0x01, name: &long, id: 0 };
+        let mut out = [0u8; 260];
+        assert_eq!(value.write_to_slice(&mut out, true), Err(Error::PayloadTooLarge));
+    }
+
+    #[derive(Debug, PartialEq, macros::BytesSerializationSized, BytesSerializable, macros::BytesDeserializable)]
+    struct Checksummed {
+        a: u8,
+        b: [u8; 2],
+        #[bytes_serialize(checksum_over = "a..checksum")]
+        checksum: u8,
+        tail: u8
+    }
+
+    #[test]
+    fn test_derived_checksum_over() {
+        /* the stored checksum is ignored when writing */
+        let value = Checksummed { a: 0x20, b: [0x18, 0x01], checksum: 0, tail: 0x81 };
+        let mut out = [0u8; 5];
+        assert!(value.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x20, 0x18, 0x01, 0xc7, 0x81]);
+
+        let parsed = Checksummed::from_bytes(&out, true).unwrap();
+        assert_eq!(parsed, Checksummed { checksum: 0xc7, ..value });
+
+        /* the tail is outside of the range */
+        out[4] = 0x00;
+        assert!(Checksummed::from_bytes(&out, true).is_ok());
+
+        out[1] = 0x19;
+        assert_eq!(Checksummed::from_bytes(&out, true), Err(Error::InvalidChecksum));
+        assert_eq!(Checksummed::from_bytes(&out, false), Err(Error::InvalidChecksum));
+    }
+
+    #[test]
+    fn test_lan_config_client() {
+        let mut selectors = [0u8; 4];
+        let mut calls = 0;
+
+        let mut client = LanConfigClient::new(0x01, 
+            |req: &GetLanConfigParamRequest, buf: &mut [u8]| {
+                assert_eq!(req.channel, 0x01);
+                selectors[calls] = req.parameter_selector;
+                calls += 1;
+
+                let data: &[u8] = match req.parameter_selector {
+                    LAN_PARAM_IP_ADDRESS  => &[192, 168, 1, 120],
+                    LAN_PARAM_MAC_ADDRESS => &[0x00, 0x25, 0x90, 0xab, 0xcd, 0xef],
+                    LAN_PARAM_SUBNET_MASK => &[255, 255, 255],
+                    _ => return Ok((0x80, 0))
+                };
+                let res = GetLanConfigParamResponse { parameter_version: 0x11, data };
+                res.write_to_slice(buf, true).map(|_| (0u8, res.size()))
+            });
+
+        assert_eq!(client.lan_ip(), Ok([192, 168, 1, 120]));
+        assert_eq!(client.lan_mac(), Ok([0x00, 0x25, 0x90, 0xab, 0xcd, 0xef]));
+        assert_eq!(client.lan_subnet(), Err(CallError::Parse(Error::PayloadTooSmall)));
+        assert_eq!(client.lan_gateway(), Err(CallError::CompletionCode(0x80)));
+
+        assert_eq!(selectors, [
+            LAN_PARAM_IP_ADDRESS, LAN_PARAM_MAC_ADDRESS,
+            LAN_PARAM_SUBNET_MASK, LAN_PARAM_DEFAULT_GATEWAY
+        ]);
+    }
+
+    #[test]
+    fn test_set_lan_config_param() {
+        let req = SetLanConfigParamRequest { channel: 0x01, parameter_selector: LAN_PARAM_IP_ADDRESS, data: &[10, 0, 0, 2] };
+        let mut out = [0u8; 6];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x01, 0x03, 10, 0, 0, 2]);
+
+        let msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x0c, peer_lun: 0,
+            local_addr: 0x81, seqnum: 1, local_lun: 0,
+            cmd: 0x01, data: IpmiData::Request(&out)
+        };
+        assert_eq!(decode_command(&msg), Some(KnownCommand::SetLanConfigParam(SetLanConfigParam::Request(req))));
+    }
+
+    #[test]
+    fn test_rmcp_write_into() {
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        let decoded = RmcpMessage::from_bytes(&req_bytes, true).unwrap();
+
+        /* a datagram sized buffer, only the returned length is meaningful */
+        let mut buf = [0u8; 1024];
+        let n = decoded.write_into(&mut buf, true).unwrap();
+        assert_eq!(n, req_bytes.len());
+        assert_eq!(buf[..n], req_bytes);
+
+        let mut small = [0u8; 22];
+        assert_eq!(decoded.write_into(&mut small, true), Err(Error::OutBufferTooSmall));
+        assert_eq!(decoded.write_to_slice(&mut small, true), Err(Error::OutBufferTooSmall));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_to_vec() {
+        let rmcp_asf_ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00];
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        assert_eq!(ping.to_vec(true).unwrap(), rmcp_asf_ping);
+
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        if let RmcpContent::Ipmi15(packet) = RmcpMessage::from_bytes(&req_bytes, true).unwrap().data {
+            assert_eq!(packet.to_vec(true).unwrap(), req_bytes[4..]);
+        } else {
+            panic!("Should decode as IPMI 1.5 packet")
+        }
+    }
+
+    #[test]
+    fn test_asf_pong_extended_oem_data() {
+        let pong_bytes = [
+            0x06, 0x00, 0xff, 0x06,
+            0x00, 0x00, 0x11, 0xbe, 0x40, 0x00, 0x00, 0x14,
+            0x00, 0x00, 0x11, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x81, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xde, 0xad, 0xbe, 0xef
+        ];
+
+        assert_eq!(RmcpMessage::from_bytes(&pong_bytes, true), Err(Error::PayloadTooLarge));
+
+        let decoded = RmcpMessage::from_bytes(&pong_bytes, false).unwrap();
+        if let RmcpContent::Asf(asf) = &decoded.data {
+            assert_eq!(asf.data_len, 20);
+            assert_eq!(asf.data, AsfData::Pong {
+                iana: 4542, oem_defined: 0, entities: 0x81, interactions: 0x00,
+                oem_data: &[0xde, 0xad, 0xbe, 0xef]
+            });
+        } else {
+            panic!("Should decode as ASF message")
+        }
+
+        let mut out = [0u8; 32];
+        assert_eq!(decoded.write_into(&mut out, false), Ok(32));
+        assert_eq!(out, pong_bytes);
+    }
+
+    #[test]
+    fn test_asf_pong_round_trip() {
+        /* the 16 data bytes a BMC answers a ping with, reserved bytes included */
+        let pong_bytes = [
+            0x06, 0x00, 0xff, 0x06,
+            0x00, 0x00, 0x11, 0xbe, 0x40, 0x00, 0x00, 0x10,
+            0x00, 0x00, 0x11, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x81, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ];
+
+        let decoded = RmcpMessage::from_bytes(&pong_bytes, true).unwrap();
+        let RmcpContent::Asf(asf) = &decoded.data else {
+            panic!("Should decode as ASF message")
+        };
+        assert!(asf.is_pong());
+        assert_eq!(asf, &AsfMessage::pong(4542, 0, 0x81, 0x00));
+        assert_eq!(asf.size(), 24);
+
+        let mut out = [0u8; 28];
+        assert_eq!(decoded.write_into(&mut out, true), Ok(28));
+        assert_eq!(out, pong_bytes);
+    }
+
+    #[test]
+    fn test_asf_legacy_pong_strict() {
+        /* data length 10, without the reserved bytes */
+        let pong_bytes = [
+            0x06, 0x00, 0xff, 0x06,
+            0x00, 0x00, 0x11, 0xbe, 0x40, 0x00, 0x00, 0x0a,
+            0x00, 0x00, 0x11, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x81, 0x00
+        ];
+        assert_eq!(RmcpMessage::from_bytes(&pong_bytes, true), Err(Error::PayloadTooSmall));
+
+        let decoded = RmcpMessage::from_bytes(&pong_bytes, false).unwrap();
+        let RmcpContent::Asf(asf) = &decoded.data else {
+            panic!("Should decode as ASF message")
+        };
+        assert_eq!(asf.data_len, 10);
+        assert!(!asf.is_pong());
+    }
+
+    #[test]
+    fn test_completion_code_oem_meaning() {
+        static OEM_CODES: [CompletionCodeMeaning; 2] = [
+            CompletionCodeMeaning { netfn: 0x0a, cmd: 0x43, code: 0x81, meaning: "Cannot execute command, SEL erase in progress" },
+            CompletionCodeMeaning { netfn: 0x30, cmd: 0x01, code: 0xc1, meaning: "OEM: feature license missing" }
+        ];
+
+        let code = CompletionCode(0x81);
+        assert_eq!(code.describe(), None);
+        assert_eq!(code.describe_for(0x0b, 0x43, &OEM_CODES), 
+            Some("Cannot execute command, SEL erase in progress"));
+        assert_eq!(code.describe_for(0x0a, 0x40, &OEM_CODES), None);
+
+        /* registered meanings take precedence over the generic ones */
+        let code = CompletionCode::INVALID_COMMAND;
+        assert_eq!(code.describe_for(0x30, 0x01, &OEM_CODES), Some("OEM: feature license missing"));
+        assert_eq!(code.describe_for(0x06, 0x01, &OEM_CODES), Some("Invalid command"));
+    }
+
+    #[test]
+    fn test_ipmi_netfn_parity_mismatch() {
+        let mut out = [0u8; 16];
+        let mut msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x81, seqnum: 0x01, local_lun: 0,
+            cmd: 0x38, data: IpmiData::Response(0x00, &[0x01])
+        };
+
+        /* even netfn carrying a response */
+        assert_eq!(msg.write_to_slice(&mut out, true), Err(Error::InvalidConfiguration));
+        assert!(msg.write_to_slice(&mut out, false).is_ok());
+
+        /* odd netfn carrying a request */
+        msg.netfn = 0x07;
+        msg.data  = IpmiData::Request(&[0x0e, 0x04]);
+        assert_eq!(msg.write_to_slice(&mut out, true), Err(Error::InvalidConfiguration));
+        assert!(msg.write_to_slice(&mut out, false).is_ok());
+
+        msg.netfn = 0x06;
+        assert!(msg.write_to_slice(&mut out, true).is_ok());
+    }
+
+    #[test]
+    fn test_decode_all_commands() {
+        let datagram = [
+            /* GetChannelAuthCapabilities Request */
+            0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,
+            0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31,
+            /* GetChannelAuthCapabilities Response */
+            0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x10,
+            0x81,0x1c,0x63,0x20,0x04,0x38,0x00,0x01,0x04,0x14,0x00,0xd6,0xc1,0x00,0x00,0xf4,
+            /* ASF ping */
+            0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00
+        ];
+
+        let mut commands = decode_all_commands(&datagram, true);
+
+        assert!(matches!(commands.next(), Some(Ok(AnyIpmiCommand::Known(
+            KnownCommand::GetChannelAuthCap(GetChannelAuthCap::Request(_)))))));
+        assert!(matches!(commands.next(), Some(Ok(AnyIpmiCommand::Known(
+            KnownCommand::GetChannelAuthCap(GetChannelAuthCap::Response(0, _)))))));
+        assert_eq!(commands.next(), Some(Ok(AnyIpmiCommand::Raw(
+            RmcpMessage::from_asf(AsfMessage::ping())))));
+        assert!(commands.next().is_none());
+
+        /* a truncated trailing frame is reported */
+        let mut commands = decode_all_commands(&datagram[..30], true);
+        assert!(matches!(commands.next(), Some(Ok(AnyIpmiCommand::Known(_)))));
+        assert_eq!(commands.next(), Some(Err(Error::PayloadTooSmall)));
+        assert!(commands.next().is_none());
+    }
+
+    #[test]
+    fn test_ipmi_write_to_slice_checked() {
+        let res_bytes = [0x81,0x1c,0x63,0x20,0x04,0x38,0x00,0x01,0x04,0x14,0x00,0xd6,0xc1,0x00,0x00,0xf4];
+        let msg = IpmiMessage::from_bytes(&res_bytes, true).unwrap();
+
+        let mut out = [0u8; 16];
+        assert!(msg.write_to_slice_checked(&mut out, true).is_ok());
+        assert_eq!(out, res_bytes);
+
+        let mut small = [0u8; 15];
+        assert_eq!(msg.write_to_slice_checked(&mut small, true), Err(Error::OutBufferTooSmall));
+    }
+
+    #[test]
+    fn test_ipmi_verify_checksums() {
+        let res_bytes = [0x81,0x1c,0x63,0x20,0x04,0x38,0x00,0x01,0x04,0x14,0x00,0xd6,0xc1,0x00,0x00,0xf4];
+        let msg = IpmiMessage::from_bytes(&res_bytes, true).unwrap();
+
+        let mut out = [0u8; 16];
+        assert!(msg.write_to_slice_checked(&mut out, true).is_ok());
+        assert_eq!(IpmiMessage::verify_checksums(&out), Ok(()));
+
+        /* corrupted after writing, in the header and in the data */
+        let mut header = out;
+        header[1] ^= 0x04;
+        assert_eq!(IpmiMessage::verify_checksums(&header), Err(Error::InvalidChecksum));
+
+        let mut data = out;
+        data[9] ^= 0x01;
+        assert_eq!(IpmiMessage::verify_checksums(&data), Err(Error::InvalidChecksum));
+
+        assert_eq!(IpmiMessage::verify_checksums(&out[..2]), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_ipmi_response_completion_code_only() {
+        /* Set Session Privilege Level response failing with 0xcc, no data */
+        let res_bytes = [0x81, 0x1c, 0x63, 0x20, 0x04, 0x3b, 0xcc, 0xd5];
+        let msg = IpmiMessage::from_bytes(&res_bytes, true).unwrap();
+        assert_eq!(msg.data, IpmiData::Response(0xcc, &[]));
+        assert_eq!(msg.size(), res_bytes.len());
+
+        let mut out = [0u8; 8];
+        assert!(msg.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, res_bytes);
+
+        /* without even the completion code, the response is truncated */
+        let truncated = [0x81, 0x1c, 0x63, 0x20, 0x04, 0x3b, 0xa1];
+        assert_eq!(IpmiMessage::from_bytes(&truncated, true), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_ipmi_sequence_wraparound() {
+        let req_bytes = [0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        let mut packet = Ipmi15Packet::from_bytes(&req_bytes, true).unwrap();
+
+        assert_eq!(packet.next_seq(), 1);
+        packet.seqnum = 0xfffffffe;
+        assert_eq!(packet.next_seq(), 0xffffffff);
+        assert_eq!(packet.next_seq(), 1);
+
+        assert_eq!(packet.data.seqnum, 1);
+        assert_eq!(packet.data.next_msg_seq(), 2);
+        packet.data.seqnum = 0x3e;
+        assert_eq!(packet.data.next_msg_seq(), 0x3f);
+        assert_eq!(packet.data.next_msg_seq(), 0);
+
+        /* the message sequence number is 6 bits wide */
+        let mut out = [0u8; 9];
+        packet.data.seqnum = 0x40;
+        assert_eq!(packet.data.write_to_slice(&mut out, true), Err(Error::InvalidConfiguration));
+        packet.data.seqnum = 0x3f;
+        assert!(packet.data.write_to_slice(&mut out, true).is_ok());
+    }
+
+    #[test]
+    fn test_pet_trap() {
+        let mut pet_bytes = [0u8; 48];
+        pet_bytes[..16].copy_from_slice(&[0x11; 16]);
+        pet_bytes[16..46].copy_from_slice(&[
+            0x00, 0x2a,             /* seqnum */
+            0x12, 0x34, 0x56, 0x78, /* timestamp */
+            0xff, 0xc4,             /* utc offset, -60 minutes */
+            0x20, 0xf1, 0x08,       /* trap source, event source, severity */
+            0x20, 0x05, 0x07, 0x01, /* sensor device/number, entity/instance */
+            0x57, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, /* event data */
+            0x19,                   /* language code */
+            0x00, 0x00, 0x11, 0xbe, /* manufacturer id */
+            0x00, 0x01              /* system id */
+        ]);
+        pet_bytes[46..].copy_from_slice(&[0xaa, 0xc1]);
+
+        let pet = PetTrap::from_bytes(&pet_bytes, true).unwrap();
+        assert_eq!(pet.guid, [0x11; 16]);
+        assert_eq!(pet.seqnum, 42);
+        assert_eq!(pet.timestamp, 0x12345678);
+        assert_eq!(pet.utc_offset_minutes(), Some(-60));
+        assert_eq!(pet.event_severity, 0x08);
+        assert_eq!(pet.sensor_number, 0x05);
+        assert_eq!(pet.event_data[0], 0x57);
+        assert_eq!(pet.manufacturer_id, 4542);
+        assert_eq!(pet.system_id, 1);
+        assert_eq!(pet.oem_custom, &[0xaa, 0xc1]);
+
+        let mut out = [0u8; 48];
+        assert!(pet.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, pet_bytes);
+
+        /* the OEM end marker is only required in strict mode */
+        assert_eq!(PetTrap::from_bytes(&pet_bytes[..46], true), Err(Error::PayloadTooSmall));
+        assert!(PetTrap::from_bytes(&pet_bytes[..46], false).is_ok());
+    }
+
+    #[test]
+    fn test_write_and_split() {
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        let mut buf = [0u8; 32];
+
+        let (first, remain) = ping.write_and_split(&mut buf, true).unwrap();
+        assert_eq!(first.len(), 12);
+        let (second, remain) = ping.with_sequence_number(0x01).write_and_split(remain, true).unwrap();
+        assert_eq!(second.len(), 12);
+        assert_eq!(remain.len(), 8);
+
+        assert_eq!(RmcpMessage::from_bytes(&buf[..12], true), Ok(RmcpMessage::from_asf(AsfMessage::ping())));
+        let decoded = RmcpMessage::from_bytes(&buf[12..24], true).unwrap();
+        assert_eq!(decoded.sequence_number, 0x01);
+        assert!(matches!(decoded.data, RmcpContent::Asf(ref asf) if asf.is_ping()));
+    }
+
+    #[test]
+    fn test_ipmi_trailing_padding() {
+        let padded = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31,0x00,0x00,0x00];
+
+        let decoded = RmcpMessage::from_bytes(&padded, false).unwrap();
+        if let RmcpContent::Ipmi15(packet) = &decoded.data {
+            assert_eq!(packet.payload_len, 9);
+            assert_eq!(packet.command_data(), &IpmiData::Request(&[0x0e, 0x04]));
+        } else {
+            panic!("Should decode as IPMI 1.5 packet")
+        }
+
+        assert!(RmcpMessage::from_bytes(&padded, true).is_err());
+        assert!(RmcpMessage::from_bytes(&padded[..23], true).is_ok());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_diagnose() {
+        use super::ipmi::diag::diagnose;
+        use alloc::string::ToString;
+
+        let rmcp_plus = [0x06, 0x00, 0xff, 0x07, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let report = diagnose(&rmcp_plus);
+        assert_eq!(report.error, Some(Error::UnsupportedProtocol));
+        assert!(report.to_string().contains("auth_type 0x06"));
+
+        let ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00];
+        assert!(diagnose(&ping).is_valid());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rmcp_hexdump() {
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        assert_eq!(ping.hexdump(), "RMCP header     06 00 ff 06\nASF header      00 00 11 be 80 00 00 00\n");
+
+        let res_bytes = [0x06, 0x00, 0xff, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09,
+                         0x81, 0x1c, 0x63, 0x20, 0x00, 0x38, 0x00, 0x01, 0xa7];
+        let dump = RmcpMessage::from_bytes(&res_bytes, true).unwrap().hexdump();
+        assert_eq!(dump.lines().collect::<alloc::vec::Vec<_>>(), [
+            "RMCP header     06 00 ff 07",
+            "session header  00 00 00 00 00 00 00 00 00",
+            "payload length  09",
+            "IPMI header     81 1c 63 20 00 38",
+            "completion code 00",
+            "IPMI data       01",
+            "checksum        a7"
+        ]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rmcp_owned() {
+        use super::ipmi::owned::*;
+
+        let inner = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x81, seqnum: 0x02, local_lun: 0,
+            cmd: 0x3b, data: IpmiData::Request(&[0x04])
+        };
+        let packet = Ipmi15Packet {
+            auth_type: IPMI_AUTH_TYPE_MD5, seqnum: 1, session_id: 0x12345678,
+            auth_code: Some(&[0xaa; 16]), payload_len: inner.size() as u8, data: inner
+        };
+        let bytes = RmcpMessage::from_ipmi15(packet, 0xff).to_vec(true).unwrap();
+
+        /* the owned message outlives the buffer it was decoded from */
+        let owned = {
+            let buf = bytes.clone();
+            RmcpMessage::from_bytes(&buf, true).unwrap().to_owned()
+        };
+
+        let RmcpContentOwned::Ipmi15(packet) = &owned.data else {
+            panic!("Should decode as IPMI 1.5")
+        };
+        assert_eq!(packet.auth_code.as_deref(), Some(&[0xaa; 16][..]));
+        assert_eq!(packet.data.data, IpmiDataOwned::Request(alloc::vec![0x04]));
+        assert_eq!(owned.as_borrowed().to_vec(true), Ok(bytes.clone()));
+        assert_eq!(owned.as_borrowed(), RmcpMessage::from_bytes(&bytes, true).unwrap());
+
+        let pong = RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00));
+        assert_eq!(pong.to_owned().as_borrowed(), pong);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_lan_client_loopback() {
+        use std::net::UdpSocket;
+        use std::time::Duration;
+        use super::ipmi::client::IpmiLanClient;
+
+        let bmc = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = IpmiLanClient::connect(bmc.local_addr().unwrap()).unwrap();
+
+        let responder = std::thread::spawn(move || {
+            let mut buf = [0u8; 64];
+            let (len, from) = bmc.recv_from(&mut buf).unwrap();
+            let ping = RmcpMessage::from_bytes(&buf[..len], true).unwrap();
+            assert_eq!(ping, RmcpMessage::from_asf(AsfMessage::ping()));
+
+            let pong = RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00));
+            bmc.send_to(&pong.to_vec(true).unwrap(), from).unwrap();
+        });
+
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        assert_eq!(client.send(&ping).unwrap(), ping.size());
+
+        let pong = client.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(pong.as_borrowed(), RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00)));
+        responder.join().unwrap();
+
+        /* nothing else is coming */
+        assert!(matches!(client.recv_timeout(Duration::from_millis(10)), Err(CallError::Transport(_))));
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_lan_client_call() {
+        use std::net::UdpSocket;
+        use std::time::Duration;
+        use super::ipmi::client::IpmiLanClient;
+
+        let bmc = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut client = IpmiLanClient::connect(bmc.local_addr().unwrap()).unwrap().with_max_message_len(64);
+
+        let responder = std::thread::spawn(move || {
+            let pong = RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00)).to_vec(true).unwrap();
+            let mut buf = [0u8; 64];
+            for _ in 0..2 {
+                let (_, from) = bmc.recv_from(&mut buf).unwrap();
+                bmc.send_to(&pong, from).unwrap();
+            }
+        });
+
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        for _ in 0..2 {
+            let pong = client.call(&ping, Duration::from_secs(5)).unwrap();
+            assert_eq!(pong, RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00)));
+        }
+        responder.join().unwrap();
+
+        /* the request must fit in the buffer */
+        let mut small = IpmiLanClient::connect("127.0.0.1:9").unwrap().with_max_message_len(8);
+        assert!(matches!(small.call(&ping, Duration::from_millis(10)), Err(CallError::Parse(Error::OutBufferTooSmall))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        let msg = RmcpMessage::from_bytes(&req_bytes, true).unwrap();
+
+        /* byte slices are written as arrays of bytes */
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["data"]["Ipmi15"]["auth_code"], serde_json::Value::Null);
+        assert_eq!(json["data"]["Ipmi15"]["data"]["cmd"], 0x38);
+        assert_eq!(json["data"]["Ipmi15"]["data"]["data"]["Request"], serde_json::json!([0x0e, 0x04]));
+
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        let json = serde_json::to_string(&ping).unwrap();
+        assert_eq!(serde_json::from_str::<RmcpMessage>(&json).unwrap(), ping);
+    }
+
+    #[test]
+    fn test_ipmi_response_for() {
+        let req_bytes = [0x20, 0x18, 0xc8, 0x81, 0x04, 0x38, 0x0e, 0x04, 0x31];
+        let req = IpmiMessage::from_bytes(&req_bytes, true).unwrap();
+
+        let res_data = [0x01, 0x04, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let res = req.response_for(0x00, &res_data);
+
+        assert_eq!(res.netfn, 0x07);
+        assert_eq!(res.cmd, 0x38);
+        assert_eq!(res.seqnum, req.seqnum);
+        assert_eq!(res.rq_addr(), req.rq_addr());
+        assert_eq!(res.rs_addr(), req.rs_addr());
+        assert_eq!(res.rq_lun(), req.rq_lun());
+
+        let mut out = [0u8; 16];
+        assert!(res.write_to_slice_checked(&mut out, true).is_ok());
+        assert_eq!(&out[..6], &[0x81, 0x1c, 0x63, 0x20, 0x04, 0x38]);
+        assert_eq!(IpmiMessage::from_bytes(&out, true), Ok(res));
+    }
+
+    #[test]
+    fn test_command_as_map_key() {
+        extern crate std;
+        use std::collections::{HashMap, HashSet};
+
+        let watchdog = KnownCommand::ResetWatchdogTimer(
+            ResetWatchdogTimer::Request(ResetWatchdogTimerRequest {}));
+        let reserve = KnownCommand::ReserveSel(
+            ReserveSel::Response(0x00, ReserveSelResponse { reservation_id: 0x1234 }));
+        let reserve_other = KnownCommand::ReserveSel(
+            ReserveSel::Response(0x00, ReserveSelResponse { reservation_id: 0x5678 }));
+
+        let mut handlers = HashMap::new();
+        handlers.insert(watchdog.netfn_cmd(), "watchdog");
+        handlers.insert(reserve.netfn_cmd(), "sel");
+        assert_eq!(handlers.len(), 2);
+        assert_eq!(handlers.get(&reserve_other.netfn_cmd()), Some(&"sel"));
+        assert_eq!(watchdog.netfn_cmd(), (0x06, 0x22));
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(reserve.clone()));
+        assert!(!seen.insert(reserve));
+        assert!(seen.insert(reserve_other));
+    }
+
+    #[test]
+    fn test_completion_code_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        assert!(CompletionCode::SUCCESS.is_success());
+        assert!(!CompletionCode::NODE_BUSY.is_success());
+        assert_eq!(CompletionCode::INVALID_DATA_FIELD.to_string(), "Invalid data field in request (0xcc)");
+        assert_eq!(CompletionCode(0x81).to_string(), "Completion code 0x81");
+
+        assert_eq!(IpmiData::Response(0xc1, &[]).completion_code(), Some(CompletionCode::INVALID_COMMAND));
+        assert_eq!(IpmiData::Request(&[]).completion_code(), None);
+    }
+
+    #[test]
+    fn test_session_challenge_username() {
+        let req = GetSessionChallengeRequest::new(IPMI_AUTH_TYPE_MD5, "ADMIN").unwrap();
+        assert_eq!(req.username[..6], [b'A', b'D', b'M', b'I', b'N', 0]);
+        assert_eq!(req.username[5..], [0; 11]);
+        assert_eq!(req.username_str(), Ok("ADMIN"));
+
+        let full = GetSessionChallengeRequest::new(IPMI_AUTH_TYPE_NONE, "0123456789abcdef").unwrap();
+        assert_eq!(full.username_str(), Ok("0123456789abcdef"));
+        assert_eq!(GetSessionChallengeRequest::new(IPMI_AUTH_TYPE_NONE, "0123456789abcdefg"),
+            Err(Error::PayloadTooLarge));
+
+        let anonymous = GetSessionChallengeRequest::new(IPMI_AUTH_TYPE_NONE, "").unwrap();
+        assert_eq!(anonymous.username_str(), Ok(""));
+        assert!(GetSessionChallengeRequest { auth_type: 0, username: [0xff; 16] }.username_str().is_err());
+    }
+
+    #[test]
+    fn test_set_session_priv_level() {
+        let req = SetSessionPrivLevelRequest::from_bytes(&[0x04], true).unwrap();
+        assert_eq!(req.priv_level, PrivLevel::Admin);
+        assert_eq!(req.priv_level.as_u8(), IPMI_PRIV_LEVEL_ADMIN);
+
+        assert_eq!(SetSessionPrivLevelRequest::from_bytes(&[0x06], true), Err(Error::InvalidConfiguration));
+        let oem = SetSessionPrivLevelRequest::from_bytes(&[0x06], false).unwrap();
+        assert_eq!(oem.priv_level, PrivLevel::Other(0x06));
+
+        let mut out = [0u8; 1];
+        assert_eq!(oem.write_to_slice(&mut out, true), Err(Error::InvalidConfiguration));
+        assert!(oem.write_to_slice(&mut out, false).is_ok());
+        assert_eq!(out, [0x06]);
+    }
+
+    #[test]
+    fn test_looks_like_rmcp() {
+        let ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00];
+        let ack  = [0x06, 0x00, 0x01, 0x86];
+        assert!(RmcpMessage::looks_like_rmcp(&ping));
+        assert!(RmcpMessage::looks_like_rmcp(&ack));
+
+        assert!(!RmcpMessage::looks_like_rmcp(&ping[..3]));
+        assert!(!RmcpMessage::looks_like_rmcp(&[0x45, 0x00, 0x00, 0x54, 0x12, 0x34]));
+        assert!(!RmcpMessage::looks_like_rmcp(&[0x06, 0x01, 0xff, 0x07]));
+        assert!(!RmcpMessage::looks_like_rmcp(&[]));
+    }
+
+    #[test]
+    fn test_rmcp_from_bytes_partial() {
+        let buf = [
+            0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00,
+            0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31,
+            0x06, 0x00
+        ];
+
+        let (ping, rest) = RmcpMessage::from_bytes_partial(&buf, true).unwrap();
+        assert_eq!(ping, RmcpMessage::from_asf(AsfMessage::ping()));
+        assert_eq!(rest.len(), 25);
+
+        let (ipmi, rest) = RmcpMessage::from_bytes_partial(rest, true).unwrap();
+        assert!(matches!(ipmi.data, RmcpContent::Ipmi15(_)));
+        assert_eq!(rest, &[0x06, 0x00]);
+
+        assert_eq!(RmcpMessage::from_bytes_partial(rest, true), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_rmcp_oem_iana_round_trip() {
+        let oem = RmcpMessage {
+            version: 0x06,
+            reserved: 0x00,
+            sequence_number: 0xff,
+            message_class: 0x08,
+            data: RmcpContent::Oem { iana: 4542, data: &[0xaa, 0xbb] }
+        };
+
+        let mut out = [0u8; 10];
+        assert!(oem.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(&out[4..8], &[0x00, 0x00, 0x11, 0xbe]);
+        assert_eq!(RmcpMessage::from_bytes(&out, true), Ok(oem));
+    }
+
+    /* feed every decoder with `bytes` in both modes, none of them may panic */
+    fn decode_everything(bytes: &[u8]) {
+        for strict in [true, false] {
+            if let Ok(msg) = RmcpMessage::from_bytes(bytes, strict) {
+                for prefix in [OemLengthPrefix::U8, OemLengthPrefix::U16Le, OemLengthPrefix::U16Be] {
+                    if let Some(iter) = msg.data.oem_ipmi_messages(prefix, strict) {
+                        iter.for_each(drop);
+                    }
+                }
+            }
+            let _ = RmcpMessage::from_bytes_partial(bytes, strict);
+            let _ = RmcpMessage::frame_len(bytes);
+            RmcpFrames::new(bytes, strict).for_each(drop);
+            decode_all_commands(bytes, strict).for_each(drop);
+            for integrity_len in [0, 12, 20, usize::MAX] {
+                let _ = Datagram::from_bytes(bytes, integrity_len, strict);
+            }
+            let _ = AsfMessage::from_bytes(bytes, strict);
+            let _ = Ipmi15Packet::from_bytes(bytes, strict);
+            if let Ok(msg) = IpmiMessage::from_bytes(bytes, strict) {
+                let _ = decode_command(&msg);
+            }
+            let _ = PetTrap::from_bytes(bytes, strict);
+            if let Ok(sdr) = SdrFullRecord::from_bytes(bytes, strict) {
+                for raw in [0x00, 0x7f, 0x80, 0xff] {
+                    let _ = sdr.convert_reading_fixed(raw);
+                }
+            }
+            if let Ok(req) = SendMessageRequest::from_bytes(bytes, strict) {
+                let _ = req.inner(strict);
+            }
+            let _ = GetSelInfoResponse::from_bytes(bytes, strict);
+            let _ = GetSelEntryResponse::from_bytes(bytes, strict);
+            let _ = GetSessionChallengeResponse::from_bytes(bytes, strict);
+            let _ = ActivateSessionResponse::from_bytes(bytes, strict);
+        }
+        #[cfg(feature = "alloc")]
+        let _ = super::ipmi::diag::diagnose(bytes);
+    }
+
+    #[test]
+    fn test_decoders_never_panic() {
+        let frames: [&[u8]; 4] = [
+            &[0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00],
+            &[0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31],
+            &[0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x40, 0x00, 0x00, 0x10, 0x00, 0x00, 0x11, 0xbe, 0x00, 0x00, 0x00, 0x00, 0x81, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            &[0x06, 0x00, 0xff, 0x08, 0x00, 0x00, 0x11, 0xbe, 0x09, 0x20, 0x18, 0xc8, 0x81, 0x04, 0x38, 0x0e, 0x04, 0x31],
+        ];
+
+        /* every truncation and every single byte corruption of valid frames */
+        for frame in frames {
+            let mut buf = [0u8; 64];
+            buf[..frame.len()].copy_from_slice(frame);
+            for len in 0..=frame.len() {
+                decode_everything(&buf[..len]);
+            }
+            for idx in 0..frame.len() {
+                for value in [0x00, 0x01, 0x06, 0x07, 0x08, 0x80, 0xff] {
+                    buf[idx] = value;
+                    decode_everything(&buf[..frame.len()]);
+                }
+                buf[idx] = frame[idx];
+            }
+        }
+
+        /* and random byte strings, from a fixed seed xorshift */
+        let mut state = 0x2545f4914f6cdd1du64;
+        let mut buf = [0u8; 96];
+        for _ in 0..4096 {
+            for byte in buf.iter_mut() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *byte = state as u8;
+            }
+            /* keep a plausible RMCP header on half of them to get deeper */
+            if state & 1 == 1 {
+                buf[..2].copy_from_slice(&[0x06, 0x00]);
+                buf[3] = [0x06, 0x07, 0x08, 0x86][(state >> 8) as usize % 4];
+                buf[4] = [0x00, 0x02, 0x06][(state >> 16) as usize % 3];
+            }
+            decode_everything(&buf[..(state >> 24) as usize % buf.len()]);
+        }
+    }
+
+    #[test]
+    fn test_rmcp_ack_for() {
+        let req_bytes = [0x06, 0x00, 0x05, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        let msg = RmcpMessage::from_bytes(&req_bytes, true).unwrap();
+
+        let ack = RmcpMessage::ack_for(&msg);
+        assert_eq!(ack.message_class, MSG_CLASS_IPMI | MSG_CLASS_ACK);
+        assert_eq!(ack.sequence_number, 0x05);
+
+        let mut out = [0u8; 4];
+        assert!(ack.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x06, 0x00, 0x05, 0x87]);
+        assert!(matches!(RmcpMessage::from_bytes(&out, true).unwrap().data, RmcpContent::Ack));
+    }
+
+    #[test]
+    fn test_rmcp_ipmi_ack() {
+        let ack_bytes = [0x06, 0x00, 0x05, 0x87];
+        let ack = RmcpMessage::from_bytes(&ack_bytes, true).unwrap();
+        assert_eq!(ack.data, RmcpContent::Ack);
+        assert!(ack.is_ack());
+        assert_eq!(ack.class(), MSG_CLASS_IPMI);
+        assert_eq!(ack.message_class, MSG_CLASS_IPMI | MSG_CLASS_ACK);
+
+        let trailing = [0x06, 0x00, 0x05, 0x87, 0x00];
+        assert_eq!(RmcpMessage::from_bytes(&trailing, true), Err(Error::PayloadTooLarge));
+        assert_eq!(RmcpMessage::from_bytes(&trailing, false), Ok(ack));
+    }
+
+    #[test]
+    fn test_rmcp_expects_ack() {
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        assert!(!ping.expects_ack());
+        let ping = ping.with_sequence_number(0x05);
+        assert!(ping.expects_ack());
+
+        /* an ACK is never acknowledged, whatever its sequence number */
+        assert!(!RmcpMessage::ack_for(&ping).expects_ack());
+    }
+
+    #[test]
+    fn test_rmcp_classify() {
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+        assert_eq!(ping.classify(), RmcpKind::AsfPing);
+        assert_eq!(RmcpMessage::ack_for(&ping).classify(), RmcpKind::Ack);
+        assert_eq!(RmcpMessage::from_asf(AsfMessage::pong(4542, 0, 0x81, 0x00)).classify(), RmcpKind::AsfPong);
+
+        let reset = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x10, 0x01, 0x00, 0x00];
+        assert_eq!(RmcpMessage::from_bytes(&reset, true).unwrap().classify(), RmcpKind::AsfOther);
+
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x09,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        assert_eq!(RmcpMessage::from_bytes(&req_bytes, true).unwrap().classify(), RmcpKind::Ipmi15);
+
+        let oem = [0x06, 0x00, 0xff, 0x08, 0x00, 0x00, 0x11, 0xbe, 0x01];
+        assert_eq!(RmcpMessage::from_bytes(&oem, true).unwrap().classify(), RmcpKind::Oem);
+
+        let other = [0x06, 0x00, 0xff, 0x09, 0x01, 0x02];
+        assert_eq!(RmcpMessage::from_bytes(&other, false).unwrap().classify(), RmcpKind::Other);
+
+        let mut ping_bytes = [0u8; 12];
+        assert!(ping.write_to_slice(&mut ping_bytes, true).is_ok());
+        for (bytes, kind) in [
+            (&[0x06, 0x00, 0x05, 0x87][..], RmcpKind::Ack),
+            (&ping_bytes, RmcpKind::AsfPing),
+            (&reset, RmcpKind::AsfOther),
+            (&req_bytes, RmcpKind::Ipmi15),
+            (&oem, RmcpKind::Oem),
+            (&other, RmcpKind::Other)
+        ] {
+            assert_eq!(RmcpMessage::classify_bytes(bytes), Some(kind));
+        }
+
+        /* a RMCP+ Close Session, classified without parsing the session */
+        let rmcpp = [
+            0x06, 0x00, 0xff, 0x07,
+            0x06, 0x00, 0x00, 0x2a, 0x00, 0x02, 0x03, 0x00, 0x00, 0x00, 0x0b, 0x00,
+            0x20, 0x18, 0xc8, 0x81, 0x08, 0x3c, 0x00, 0x2a, 0x00, 0x02, 0x0f
+        ];
+        assert_eq!(RmcpMessage::classify_bytes(&rmcpp), Some(RmcpKind::Ipmi20));
+
+        assert_eq!(RmcpMessage::classify_bytes(&[0x06, 0x00, 0xff, 0x06, 0x00]), None);
+        assert_eq!(RmcpMessage::classify_bytes(&[0x06, 0x00, 0xff]), None);
+    }
+
+    #[test]
+    fn test_channel_access() {
+        let res = GetChannelAccessResponse::from_bytes(&[0x22, 0x04], true).unwrap();
+        assert_eq!(res.access_mode(), CHANNEL_ACCESS_MODE_ALWAYS);
+        assert!(res.is_alerting_disabled());
+        assert!(!res.is_per_msg_auth_disabled());
+        assert_eq!(res.priv_level_limit(), Some(PrivLevel::Admin));
+
+        let req = SetChannelAccessRequest {
+            channel: 0x01,
+            channel_access: CHANNEL_ACCESS_VOLATILE | CHANNEL_ACCESS_MODE_DISABLED,
+            priv_limit: CHANNEL_ACCESS_VOLATILE | IPMI_PRIV_LEVEL_USER
+        };
+        let mut out = [0u8; 3];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x01, 0x80, 0x82]);
+
+        /* Get Channel Access request, channel 1, volatile settings */
+        let msg = [0x20, 0x18, 0xc8, 0x81, 0x04, 0x41, 0x01, 0x80, 0xb9];
+        let msg = IpmiMessage::from_bytes(&msg, true).unwrap();
+        assert_eq!(decode_command(&msg), Some(KnownCommand::GetChannelAccess(
+            GetChannelAccess::Request(GetChannelAccessRequest { channel: 0x01, access_type: 0x80 }))));
+    }
+
+    #[test]
+    fn test_channel_info() {
+        /* channel 1, 802.3 LAN, IPMB-1.0 protocol, multi-session with 2 active, IPMI IANA */
+        let res_bytes = [0x01, 0x04, 0x01, 0x82, 0xf2, 0x1b, 0x00, 0x00, 0x00];
+        let data = IpmiData::Response(0x00, &res_bytes);
+        let Some(GetChannelInfo::Response(0x00, res)) = GetChannelInfo::from_data(&data) else {
+            panic!("Should decode as GetChannelInfo::Response")
+        };
+        assert_eq!(res.channel_number(), ChannelNumber(0x01));
+        assert_eq!(res.medium(), Some(ChannelMedium::Lan8023));
+        assert_eq!(res.protocol(), Some(ChannelProtocol::Ipmb));
+        assert_eq!(res.active_sessions(), 2);
+
+        assert_eq!(ChannelMedium::from_u8(0x0c).map(|m| m.as_u8()), Some(0x0c));
+        assert_eq!(ChannelMedium::from_u8(0x60), None);
+        assert_eq!(ChannelProtocol::from_u8(0x1d), Some(ChannelProtocol::Oem(0x1d)));
+        assert_eq!(ChannelProtocol::from_u8(0x03), None);
+        assert_eq!(ChannelProtocol::Kcs.as_u8(), 0x05);
+    }
+
+    #[test]
+    fn test_user_management() {
+        let res = GetUserAccessResponse::from_bytes(&[0x0a, 0x42, 0x01, 0x34], true).unwrap();
+        assert_eq!((res.max_users(), res.enabled_users(), res.fixed_names()), (10, 2, 1));
+        assert!(res.ipmi_messaging_enabled() && res.link_auth_enabled() && !res.is_callback_only());
+        assert_eq!(res.priv_level_limit(), Some(PrivLevel::Admin));
+
+        let req = SetUserNameRequest::new(0x03, "operator").unwrap();
+        let mut out = [0u8; 17];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out[..9], [0x03, b'o', b'p', b'e', b'r', b'a', b't', b'o', b'r']);
+        assert_eq!(out[9..], [0; 8]);
+        assert_eq!(SetUserNameRequest::new(0x03, "a_name_longer_than_16"), Err(Error::PayloadTooLarge));
+
+        let data = IpmiData::Response(0x00, &out[1..]);
+        let Some(GetUserName::Response(0x00, res)) = GetUserName::from_data(&data) else {
+            panic!("Should decode as GetUserName::Response")
+        };
+        assert_eq!(res.name_str(), Ok("operator"));
+    }
+
+    #[test]
+    fn test_serial_basic_mode() {
+        use super::ipmi::serial::*;
+
+        let msg = IpmiMessage {
+            peer_addr: 0x20, netfn: 0x06, peer_lun: 0,
+            local_addr: 0x81, seqnum: 0x01, local_lun: 0,
+            cmd: 0x45, data: IpmiData::Request(&[0x02, 0xa0, 0x1b, 0xaa])
+        };
+
+        let mut frame = [0u8; 32];
+        let len = frame_basic_mode(&msg, &mut frame).unwrap();
+        assert_eq!(frame[..len], [
+            0xa0,
+            0x20, 0x18, 0xc8, 0x81, 0x04, 0x45,
+            0x02, 0xaa, 0xb0, 0xaa, 0x3b, 0xaa, 0xba, 0xcf,
+            0xa5
+        ]);
+
+        let mut buf = [0u8; 32];
+        assert_eq!(deframe_basic_mode(&frame[..len], &mut buf).as_ref(), Ok(&msg));
+
+        /* a handshake before the frame is skipped */
+        let mut stream = [0u8; 33];
+        stream[0] = BASIC_MODE_HANDSHAKE;
+        stream[1..len + 1].copy_from_slice(&frame[..len]);
+        assert!(deframe_basic_mode(&stream[..len + 1], &mut buf).is_ok());
+
+        /* unterminated, and cut after an escape */
+        assert_eq!(deframe_basic_mode(&frame[..len - 1], &mut buf), Err(Error::PayloadTooSmall));
+        assert_eq!(deframe_basic_mode(&frame[..9], &mut buf), Err(Error::PayloadTooSmall));
+
+        assert_eq!(frame_basic_mode(&msg, &mut [0u8; 15]), Err(Error::OutBufferTooSmall));
+    }
+
+    #[test]
+    fn test_sol_payload() {
+        let sol_bytes = [0x03, 0x02, 0x05, SOL_STATUS_BREAK_DETECTED, b'l', b'o', b'g', b'i', b'n'];
+        let sol = SolPayload::from_bytes(&sol_bytes, true).unwrap();
+
+        assert_eq!(sol.packet_seq_number(), 3);
+        assert_eq!(sol.ack_seq_number(), 2);
+        assert_eq!(sol.accepted_chars, 5);
+        assert!(!sol.is_ack_only());
+        assert_eq!(sol.data, b"login");
+
+        let mut out = [0u8; 9];
+        assert!(sol.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, sol_bytes);
+
+        let ack = SolPayload::from_bytes(&[0x00, 0x03, 0x05, 0x00], true).unwrap();
+        assert!(ack.is_ack_only());
+        assert!(ack.data.is_empty());
+
+        let req = ActivateSolPayloadRequest::from_bytes(&[PAYLOAD_TYPE_SOL, 0x01, 0xc6, 0x00, 0x00, 0x00], true).unwrap();
+        assert_eq!(req.payload_type, PAYLOAD_TYPE_SOL);
+    }
+
+    /* 
+     * The same property as a cargo-fuzz target:
+     *
+     *   fuzz_target!(|msg: RmcpMessage| {
+     *       let mut buf = [0u8; 512];
+     *       msg.write_to_slice(&mut buf, true).unwrap();
+     *       let buf = &buf[..msg.size()];
+     *       assert_eq!(RmcpMessage::from_bytes(buf, true), Ok(msg));
+     *   });
+     */
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_round_trip() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut seed = [0u8; 1024];
+        for _ in 0..512 {
+            for byte in seed.iter_mut() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *byte = state as u8;
+            }
+
+            let mut u = Unstructured::new(&seed);
+            let msg = RmcpMessage::arbitrary(&mut u).unwrap();
+
+            let mut buf = [0u8; 512];
+            assert_eq!(msg.write_into(&mut buf, true), Ok(msg.size()));
+            assert_eq!(RmcpMessage::from_bytes(&buf[..msg.size()], true), Ok(msg));
+        }
+    }
+
+    #[test]
+    fn test_expected_size_mismatch_lengths() {
+        /* ASF ping claiming 2 data bytes it does not carry */
+        let ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x02];
+        assert_eq!(RmcpMessage::from_bytes(&ping, true),
+            Err(Error::ExpectedSizeMismatch { expected: 10, actual: 8 }));
+
+        /* IPMI 1.5 payload length of 8 over a 9 bytes message */
+        let req_bytes = [0x06, 0x00, 0xff, 0x07, 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x08,0x20,0x18,0xc8,0x81,0x04,0x38,0x0e,0x04,0x31];
+        assert_eq!(RmcpMessage::from_bytes(&req_bytes, true),
+            Err(Error::ExpectedSizeMismatch { expected: 8, actual: 9 }));
+
+        /* a payload length of 11 past the end of the datagram */
+        let mut req_bytes = req_bytes;
+        req_bytes[13] = 0x0b;
+        assert_eq!(RmcpMessage::from_bytes(&req_bytes, true), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_ipmi15_truncated_auth_code() {
+        let mut packet = [0u8; 35];
+        packet[0] = IPMI_AUTH_TYPE_MD5;
+        packet[1..5].copy_from_slice(&1u32.to_le_bytes());
+        packet[5..9].copy_from_slice(&0x12345678u32.to_le_bytes());
+        packet[9..25].copy_from_slice(&[0xaa; 16]);
+        packet[25] = 0x09;
+        packet[26..].copy_from_slice(&[0x20, 0x18, 0xc8, 0x81, 0x04, 0x3b, 0x04, 0x3c, 0x00]);
+
+        assert!(Ipmi15Packet::from_bytes(&packet, true).is_ok());
+
+        /* cut in the middle of the auth code */
+        assert_eq!(Ipmi15Packet::from_bytes(&packet[..17], true), Err(Error::PayloadTooSmall));
+        assert_eq!(Ipmi15Packet::from_bytes(&packet[..17], false), Err(Error::PayloadTooSmall));
+
+        /* auth code complete but the message is short of the payload length */
+        assert_eq!(Ipmi15Packet::from_bytes(&packet[..33], true), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_rmcp_oem_decoders() {
+        /* a made up vendor payload, an opcode followed by its arguments */
+        #[derive(Debug, PartialEq)]
+        struct VendorCommand<'a> { op: u8, args: &'a [u8] }
+
+        fn decode_vendor(data: &[u8], _strict: bool) -> Result<VendorCommand<'_>, Error> {
+            let (op, args) = data.split_first().ok_or(Error::PayloadTooSmall)?;
+            Ok(VendorCommand { op: *op, args })
+        }
+
+        let decoders: [(u32, OemDecoder<VendorCommand>); 1] = [(IANA_SUPERMICRO, decode_vendor)];
+
+        let oem = [0x06, 0x00, 0xff, 0x08, 0x00, 0x00, 0x2a, 0x7c, 0x01, 0xaa, 0xbb];
+        match RmcpMessage::from_bytes_with_oem(&oem, true, &decoders) {
+            Ok(OemDecoded::Decoded { message, value }) => {
+                assert_eq!(value, VendorCommand { op: 0x01, args: &[0xaa, 0xbb] });
+                assert_eq!(message, RmcpMessage::from_bytes(&oem, true).unwrap());
+            },
+            other => panic!("Should decode with the vendor decoder, got {:?}", other)
+        }
+
+        /* other vendors and classes are left alone */
+        let other = [0x06, 0x00, 0xff, 0x08, 0x00, 0x00, 0x02, 0xa2, 0x01];
+        let decoded = RmcpMessage::from_bytes_with_oem(&other, true, &decoders).unwrap();
+        assert_eq!(decoded, OemDecoded::Plain(RmcpMessage::from_bytes(&other, true).unwrap()));
+
+        let ping = [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00];
+        let decoded = RmcpMessage::from_bytes_with_oem(&ping, true, &decoders).unwrap();
+        assert_eq!(decoded.message(), &RmcpMessage::from_asf(AsfMessage::ping()));
+
+        let short = [0x06, 0x00, 0xff, 0x08, 0x00, 0x00, 0x2a, 0x7c];
+        assert_eq!(RmcpMessage::from_bytes_with_oem(&short, true, &decoders), Err(Error::PayloadTooSmall));
+    }
+
+    #[test]
+    fn test_iana_3_bytes() {
+        assert_eq!(iana_from_3([0x57, 0x01, 0x00]), IANA_INTEL);
+        assert_eq!(iana_to_3(IANA_DELL), [0xa2, 0x02, 0x00]);
+        assert_eq!(iana_from_3(iana_to_3(IANA_LENOVO)), IANA_LENOVO);
+
+        let res = GetChannelAuthCapResponse::from_bytes(&[0x01, 0x95, 0x24, 0x02, 0x7c, 0x2a, 0x00, 0x00], true).unwrap();
+        assert_eq!(res.manufacturer(), IANA_SUPERMICRO);
+        assert!(res.supports_ipmi2());
+        assert!(res.kg_configured());
+        assert!(res.supports_none() && res.supports_md5() && res.supports_straight_password());
+    }
+
+    #[test]
+    fn test_request_default() {
+        let req = GetChannelAuthCapRequest { channel_number: 0x0e, ..Default::default() };
+        assert_eq!(req, GetChannelAuthCapRequest { channel_number: 0x0e, max_priv_level: 0 });
+
+        let req = ActivateSessionRequest { auth_type: IPMI_AUTH_TYPE_MD5, ..Default::default() };
+        assert_eq!(req.challenge_string, [0u8; 16]);
+        assert_eq!(req.init_outbound_seq, 0);
+
+        let req = GetSdrRequest { bytes_to_read: 0xff, ..Default::default() };
+        let mut out = [0xaau8; 6];
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, [0x00, 0x00, 0x00, 0x00, 0x00, 0xff]);
+
+        assert!(SendMessageRequest::default().message.is_empty());
+    }
+
+    #[test]
+    fn test_serialize_to_array() {
+        let ping = RmcpMessage::from_asf(AsfMessage::ping());
+
+        let (buf, len) = serialize_to_array::<_, 12>(&ping, true).unwrap();
+        assert_eq!(len, 12);
+        assert_eq!(buf, [0x06, 0x00, 0xff, 0x06, 0x00, 0x00, 0x11, 0xbe, 0x80, 0x00, 0x00, 0x00]);
+
+        let (buf, len) = serialize_to_array::<_, 64>(&ping, true).unwrap();
+        assert_eq!(RmcpMessage::from_bytes(&buf[..len], true), Ok(ping));
+
+        let small: Result<([u8; 8], usize), _> = serialize_to_array(&RmcpMessage::from_asf(AsfMessage::ping()), true);
+        assert_eq!(small, Err(Error::OutBufferTooSmall));
+    }
+
+    #[test]
+    fn test_open_session() {
+        let req = OpenSessionRequest {
+            message_tag: 0x00,
+            max_priv_level: IPMI_PRIV_LEVEL_ADMIN,
+            reserved: [0; 2],
+            console_session_id: 0xa0a2a3a4,
+            auth: AlgorithmPayload::auth(AUTH_ALG_RAKP_HMAC_SHA1),
+            integrity: AlgorithmPayload::integrity(INTEGRITY_ALG_HMAC_SHA1_96),
+            confidentiality: AlgorithmPayload::confidentiality(CONFIDENTIALITY_ALG_AES_CBC_128)
+        };
+
+        let req_bytes = [
+            0x00, 0x04, 0x00, 0x00, 0xa4, 0xa3, 0xa2, 0xa0,
+            0x00, 0x00, 0x00, 0x08, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x08, 0x01, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x08, 0x01, 0x00, 0x00, 0x00
+        ];
+
+        let mut out = [0u8; 32];
+        assert_eq!(req.size(), 32);
+        assert!(req.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, req_bytes);
+        assert_eq!(OpenSessionRequest::from_bytes(&req_bytes, true), Ok(req));
+
+        let res_bytes = [
+            0x00, 0x00, 0x04, 0x00, 0xa4, 0xa3, 0xa2, 0xa0,
+            0x00, 0x2a, 0x00, 0x02,
+            0x00, 0x00, 0x00, 0x08, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x08, 0x01, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00
+        ];
+        let res = OpenSessionResponse::from_bytes(&res_bytes, true).unwrap();
+        assert_eq!(res.status_code, 0);
+        assert_eq!(res.managed_session_id, Some(0x02002a00));
+        assert_eq!(res.auth.as_ref().map(AlgorithmPayload::algorithm_id), Some(AUTH_ALG_RAKP_HMAC_SHA1));
+        assert_eq!(res.confidentiality.as_ref().map(AlgorithmPayload::algorithm_id), Some(CONFIDENTIALITY_ALG_NONE));
+
+        let mut out = [0u8; 36];
+        assert_eq!(res.size(), 36);
+        assert!(res.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, res_bytes);
+    }
+
+    #[test]
+    fn test_open_session_error_response() {
+        /* status 0x01, insufficient resources to create a session */
+        let res_bytes = [0x00, 0x01, 0x00, 0x00, 0xa4, 0xa3, 0xa2, 0xa0];
+
+        let res = OpenSessionResponse::from_bytes(&res_bytes, true).unwrap();
+        assert_eq!(res.status_code, 0x01);
+        assert_eq!(res.console_session_id, 0xa0a2a3a4);
+        assert_eq!(res.managed_session_id, None);
+        assert_eq!((res.auth.as_ref(), res.integrity.as_ref(), res.confidentiality.as_ref()), (None, None, None));
+
+        let mut out = [0u8; 8];
+        assert_eq!(res.size(), 8);
+        assert!(res.write_to_slice(&mut out, true).is_ok());
+        assert_eq!(out, res_bytes);
+
+        /* a successful response must carry the session id and the algorithms */
+        let mut success = res_bytes;
+        success[1] = 0x00;
+        assert_eq!(OpenSessionResponse::from_bytes(&success, true), Err(Error::PayloadTooSmall));
+        let res = OpenSessionResponse { status_code: 0x00, ..res };
+        assert_eq!(res.write_to_slice(&mut out, true), Err(Error::InvalidConfiguration));
+    }
+
+    #[test]
+    fn test_rmcpp_integrity_trailer() {
+        let auth = [0xaa; 12];
+
+        for (payload_len, pad_len) in [(13, 1), (14, 0), (15, 3), (16, 2)] {
+            let mut buf = [0u8; 40];
+            let total = append_integrity_trailer(&mut buf, payload_len, &auth).unwrap();
+            let pad_end = payload_len + pad_len;
+
+            assert_eq!(total, pad_end + 2 + auth.len());
+            assert_eq!((pad_end + 2) % 4, 0);
+            assert!(buf[payload_len..pad_end].iter().all(|b| *b == 0xff));
+            assert_eq!(buf[pad_end..pad_end + 2], [pad_len as u8, RMCPP_NEXT_HEADER]);
+            assert_eq!(buf[pad_end + 2..total], auth);
+        }
+
+        assert_eq!(append_integrity_trailer(&mut [0u8; 20], 13, &auth), Err(Error::OutBufferTooSmall));
+    }
+
+    #[test]
+    fn test_rmcpp_payload_type() {
+        let sol = PayloadType::from_u8(0x81);
+        assert_eq!(sol.kind(), Some(PayloadKind::Sol));
+        assert!(sol.is_encrypted() && !sol.is_authenticated());
+        assert_eq!(PayloadType::new(PayloadKind::Sol, true, false), sol);
+
+        let ipmi = PayloadType::new(PayloadKind::Ipmi, true, true);
+        assert_eq!(ipmi.as_u8(), 0xc0);
+        assert_eq!(ipmi.kind(), Some(PayloadKind::Ipmi));
+
+        assert_eq!(PayloadType::from_u8(0x55).kind(), Some(PayloadKind::Rakp4));
+        assert_eq!(PayloadType::from_u8(0x22).kind(), Some(PayloadKind::Oem(0x22)));
+        assert_eq!(PayloadType::from_u8(0x03).kind(), None);
+    }
+
+    #[cfg(feature = "hmac")]
+    #[test]
+    fn test_rakp_hmac() {
+        use super::ipmi::auth::*;
+
+        /* RFC 2202 and RFC 4231 test case 1 */
+        let mut sha1 = [0u8; 20];
+        rakp_hmac_sha1(&[0x0b; 20], b"Hi There", &mut sha1);
+        assert_eq!(sha1, [0xb6, 0x17, 0x31, 0x86, 0x55, 0x05, 0x72, 0x64, 0xe2, 0x8b, 0xc0, 0xb6, 0xfb, 0x37, 0x8c, 0x8e, 0xf1, 0x46, 0xbe, 0x00]);
+
+        let mut sha256 = [0u8; 32];
+        rakp_hmac_sha256(&[0x0b; 20], b"Hi There", &mut sha256);
+        assert_eq!(sha256, [0xb0, 0x34, 0x4c, 0x61, 0xd8, 0xdb, 0x38, 0x53, 0x5c, 0xa8, 0xaf, 0xce, 0xaf, 0x0b, 0xf1, 0x2b, 0x88, 0x1d, 0xc2, 0x00, 0xc9, 0x83, 0x3d, 0xa7, 0x26, 0xe9, 0x37, 0x6c, 0x2e, 0x32, 0xcf, 0xf7]);
+
+        /* RFC 2202 and RFC 4231 test case 2 */
+        rakp_hmac_sha1(b"Jefe", b"what do ya want for nothing?", &mut sha1);
+        assert_eq!(sha1, [0xef, 0xfc, 0xdf, 0x6a, 0xe5, 0xeb, 0x2f, 0xa2, 0xd2, 0x74, 0x16, 0xd5, 0xf1, 0x84, 0xdf, 0x9c, 0x25, 0x9a, 0x7c, 0x79]);
+        rakp_hmac_sha256(b"Jefe", b"what do ya want for nothing?", &mut sha256);
+        assert_eq!(sha256, [0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43]);
+
+        /* RFC 2202 and RFC 4231 test case 5, HMAC-SHA1-96 and HMAC-SHA256-128 */
+        let mut out = [0u8; 32];
+        assert_eq!(integrity_auth_code(RakpHmac::Sha1, &[0x0c; 20], b"Test With Truncation", &mut out), Ok(12));
+        assert_eq!(out[..12], [0x4c, 0x1a, 0x03, 0x42, 0x4b, 0x55, 0xe0, 0x7f, 0xe7, 0xf2, 0x7b, 0xe1]);
+        assert_eq!(integrity_auth_code(RakpHmac::Sha256, &[0x0c; 20], b"Test With Truncation", &mut out), Ok(16));
+        assert_eq!(out[..16], [0xa3, 0xb6, 0x16, 0x74, 0x73, 0x10, 0x0e, 0xe0, 0x6e, 0x0c, 0x79, 0x6c, 0x29, 0x55, 0x55, 0x2b]);
+
+        /* 
+         * The RAKP values are the HMAC checked above over the fields laid out
+         * as in IPMI 2.0 section 13.31, session IDs little endian
+         */
+        let mut console_random = [0u8; 16];
+        let mut managed_random = [0u8; 16];
+        let mut managed_guid   = [0u8; 16];
+        for i in 0..16 {
+            console_random[i] = i as u8;
+            managed_random[i] = 16 + i as u8;
+            managed_guid[i]   = 32 + i as u8;
+        }
+
+        let params = RakpParams {
+            console_session_id: 0xa0a2a3a4,
+            managed_session_id: 0x02002a00,
+            console_random,
+            managed_random,
+            managed_guid,
+            role: 0x14,
+            username: b"admin"
+        };
+        let kuid = b"password";
+
+        let sid_m = [0xa4, 0xa3, 0xa2, 0xa0];
+        let sid_c = [0x00, 0x2a, 0x00, 0x02];
+        let role_name = [0x14, 0x05, b'a', b'd', b'm', b'i', b'n'];
+
+        /* RAKP 2, SIDm | SIDc | Rm | Rc | GUIDc | ROLEm | ULENGTHm | UNAMEm */
+        let rakp2 = [&sid_m[..], &sid_c, &console_random, &managed_random, &managed_guid, &role_name].concat();
+        rakp_hmac_sha1(kuid, &rakp2, &mut sha1);
+        assert_eq!(params.rakp2_auth_code(RakpHmac::Sha1, kuid, &mut out), Ok(20));
+        assert_eq!(out[..20], sha1);
+        rakp_hmac_sha256(kuid, &rakp2, &mut sha256);
+        assert_eq!(params.rakp2_auth_code(RakpHmac::Sha256, kuid, &mut out), Ok(32));
+        assert_eq!(out, sha256);
+
+        /* RAKP 3, Rc | SIDm | ROLEm | ULENGTHm | UNAMEm */
+        let rakp3 = [&managed_random[..], &sid_m, &role_name].concat();
+        rakp_hmac_sha1(kuid, &rakp3, &mut sha1);
+        assert_eq!(params.rakp3_auth_code(RakpHmac::Sha1, kuid, &mut out), Ok(20));
+        assert_eq!(out[..20], sha1);
+
+        /* no Kg configured, Kuid keys the SIK, Rm | Rc | ROLEm | ULENGTHm | UNAMEm */
+        let sik_input = [&console_random[..], &managed_random, &role_name].concat();
+        let mut sik = [0u8; 20];
+        rakp_hmac_sha1(kuid, &sik_input, &mut sik);
+        assert_eq!(params.session_integrity_key(RakpHmac::Sha1, kuid, &mut out), Ok(20));
+        assert_eq!(out[..20], sik);
+
+        /* RAKP 4, Rm | SIDc | GUIDc truncated to 96 bits */
+        let rakp4 = [&console_random[..], &sid_c, &managed_guid].concat();
+        rakp_hmac_sha1(&sik, &rakp4, &mut sha1);
+        assert_eq!(params.rakp4_integrity_check(RakpHmac::Sha1, &sik, &mut out), Ok(12));
+        assert_eq!(out[..12], sha1[..12]);
+
+        /* K1 and K2, the constant repeated for the length of the HMAC */
+        let mut k1 = [0u8; 20];
+        rakp_hmac_sha1(&sik, &[0x01; 20], &mut sha1);
+        assert_eq!(derive_key(RakpHmac::Sha1, &sik, 1, &mut k1), Ok(20));
+        assert_eq!(k1, sha1);
+
+        let mut sik = [0u8; 32];
+        assert_eq!(params.session_integrity_key(RakpHmac::Sha256, kuid, &mut sik), Ok(32));
+        rakp_hmac_sha256(&sik, &[0x02; 32], &mut sha256);
+        assert_eq!(derive_key(RakpHmac::Sha256, &sik, 2, &mut out), Ok(32));
+        assert_eq!(out, sha256);
+
+        assert_eq!(params.rakp2_auth_code(RakpHmac::Sha256, kuid, &mut [0u8; 20]), Err(Error::OutBufferTooSmall));
+        let long = RakpParams { username: &[b'a'; 17], ..params };
+        assert_eq!(long.rakp3_auth_code(RakpHmac::Sha1, kuid, &mut out), Err(Error::PayloadTooLarge));
+    }
+
+    #[cfg(feature = "hmac")]
+    #[test]
+    fn test_auth_code_comparison() {
+        use super::ipmi::auth::*;
+
+        assert!(constant_time_eq(b"password", b"password"));
+        assert!(!constant_time_eq(b"password", b"passwore"));
+        assert!(!constant_time_eq(b"password", b"password\0"));
+        assert!(!constant_time_eq(b"", b"p"));
+        assert!(constant_time_eq(b"", b""));
+
+        let k1 = [0x11; 20];
+        let data = [0x06, 0x00, 0xff, 0x07];
+        let mut auth_code = [0u8; 12];
+        assert_eq!(integrity_auth_code(RakpHmac::Sha1, &k1, &data, &mut auth_code), Ok(12));
+        assert_eq!(verify_integrity_auth_code(RakpHmac::Sha1, &k1, &data, &auth_code), Ok(true));
+        assert_eq!(verify_integrity_auth_code(RakpHmac::Sha1, &k1, &data, &auth_code[..11]), Ok(false));
+        auth_code[0] ^= 1;
+        assert_eq!(verify_integrity_auth_code(RakpHmac::Sha1, &k1, &data, &auth_code), Ok(false));
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn test_aes_cbc_payload() {
+        use super::ipmi::crypto::*;
+
+        /* NIST SP800-38A key, K2 may be longer than the 16 bytes used */
+        let k2 = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c, 0xff, 0xff, 0xff, 0xff];
+        let iv = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+
+        /* single block from the NIST vectors, padded with a second block */
+        let nist = [0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a];
+        let mut out = [0u8; 48];
+        assert_eq!(encrypt_payload(&k2, &iv, &nist, &mut out), Ok(48));
+        assert_eq!(out[16..32], [0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9, 0x19, 0x7d]);
+
+        let payload = [0x20, 0x18, 0xc8, 0x81, 0x04, 0x38, 0x0e, 0x04, 0x31];
+        let mut out = [0u8; 32];
+        assert_eq!(encrypted_len(payload.len()), 32);
+        assert_eq!(encrypt_payload(&k2, &iv, &payload, &mut out), Ok(32));
+        assert_eq!(out[..16], iv);
+        assert_eq!(out[16..], [0x38, 0x50, 0xc6, 0xa1, 0xbb, 0x8c, 0x9c, 0x09, 0xce, 0xc1, 0x43, 0xed, 0x07, 0x3d, 0xf6, 0x4f]);
+
+        let mut plain = [0u8; 16];
+        assert_eq!(decrypt_payload(&k2, &out, &mut plain), Ok(payload.len()));
+        assert_eq!(plain[..9], payload);
+        assert_eq!(plain[9..], [1, 2, 3, 4, 5, 6, 6]);
+
+        /* corrupted ciphertext breaks the pad */
+        out[31] ^= 0x01;
+        assert_eq!(decrypt_payload(&k2, &out, &mut plain), Err(Error::InvalidConfiguration));
+        assert_eq!(decrypt_payload(&k2, &out[..24], &mut plain), Err(Error::InvalidConfiguration));
+        assert_eq!(encrypt_payload(&k2, &iv, &payload, &mut [0u8; 31]), Err(Error::OutBufferTooSmall));
+    }
+
+    /* 
+     * Round trips of generated messages. proptest values can not borrow, so
+     * the generators produce the owned parts and the messages are built from
+     * them in the test body.
+     */
+    mod proptests {
+        extern crate std;
+
+        use std::vec::Vec;
+        use proptest::collection::vec;
+        use proptest::prelude::*;
+        use super::*;
+        use super::super::ipmi::asf::{ASF_MSG_TYPE_PING, ASF_MSG_TYPE_PONG};
+
+        #[derive(Debug, Clone)]
+        struct IpmiMessageParts {
+            peer_addr: u8,
+            netfn: u8,
+            peer_lun: u8,
+            local_addr: u8,
+            seqnum: u8,
+            local_lun: u8,
+            cmd: u8,
+            completion_code: u8,
+            data: Vec<u8>
+        }
+
+        impl IpmiMessageParts {
+            fn message(&self) -> IpmiMessage<'_> {
+                IpmiMessage {
+                    peer_addr: self.peer_addr,
+                    netfn: self.netfn,
+                    peer_lun: self.peer_lun,
+                    local_addr: self.local_addr,
+                    seqnum: self.seqnum,
+                    local_lun: self.local_lun,
+                    cmd: self.cmd,
+                    /* the parser tells requests from responses by the netfn parity */
+                    data: if self.netfn % 2 == 0 {
+                        IpmiData::Request(&self.data)
+                    } else {
+                        IpmiData::Response(self.completion_code, &self.data)
+                    }
+                }
+            }
+        }
+
+        /* LUNs in 2 bits, netfn and seqnum in 6, fitting an IPMI 1.5 payload length */
+        fn ipmi_message() -> impl Strategy<Value = IpmiMessageParts> {
+            (any::<u8>(), 0u8..=0b00111111, 0u8..=0b00000011, any::<u8>(), 0u8..=0b00111111,
+             0u8..=0b00000011, any::<u8>(), any::<u8>(), vec(any::<u8>(), 0..=(255 - 8)))
+                .prop_map(|(peer_addr, netfn, peer_lun, local_addr, seqnum, local_lun, cmd, completion_code, data)|
+                    IpmiMessageParts {
+                        peer_addr, netfn, peer_lun, local_addr, seqnum, local_lun, cmd, completion_code, data
+                    })
+        }
+
+        #[derive(Debug, Clone)]
+        struct Ipmi15PacketParts {
+            auth_type: u8,
+            seqnum: u32,
+            session_id: u32,
+            auth_code: [u8; 16],
+            data: IpmiMessageParts
+        }
+
+        impl Ipmi15PacketParts {
+            fn packet(&self) -> Ipmi15Packet<'_> {
+                let data = self.data.message();
+                Ipmi15Packet {
+                    auth_type: self.auth_type,
+                    seqnum: self.seqnum,
+                    session_id: self.session_id,
+                    auth_code: (self.auth_type != IPMI_AUTH_TYPE_NONE).then_some(&self.auth_code[..]),
+                    payload_len: data.size() as u8,
+                    data
+                }
+            }
+        }
+
+        fn ipmi15_packet() -> impl Strategy<Value = Ipmi15PacketParts> {
+            (IPMI_AUTH_TYPE_NONE..=IPMI_AUTH_TYPE_OEM, any::<u32>(), any::<u32>(), any::<[u8; 16]>(), ipmi_message())
+                .prop_map(|(auth_type, seqnum, session_id, auth_code, data)|
+                    Ipmi15PacketParts { auth_type, seqnum, session_id, auth_code, data })
+        }
+
+        #[derive(Debug, Clone)]
+        enum AsfMessageParts {
+            Ping,
+            Pong { iana: u32, oem_defined: u32, entities: u8, interactions: u8 },
+            Other { iana: u32, msg_type: u8, msg_tag: u8, data: Vec<u8> }
+        }
+
+        impl AsfMessageParts {
+            fn message(&self) -> AsfMessage<'_> {
+                match self {
+                    AsfMessageParts::Ping => AsfMessage::ping(),
+                    AsfMessageParts::Pong { iana, oem_defined, entities, interactions } =>
+                        AsfMessage::pong(*iana, *oem_defined, *entities, *interactions),
+                    AsfMessageParts::Other { iana, msg_type, msg_tag, data } => AsfMessage {
+                        iana: *iana,
+                        msg_type: *msg_type,
+                        msg_tag: *msg_tag,
+                        data_len: data.len() as u8,
+                        data: AsfData::Other(data)
+                    }
+                }
+            }
+        }
+
+        fn asf_message() -> impl Strategy<Value = AsfMessageParts> {
+            prop_oneof![
+                Just(AsfMessageParts::Ping),
+                (any::<u32>(), any::<u32>(), any::<u8>(), any::<u8>())
+                    .prop_map(|(iana, oem_defined, entities, interactions)|
+                        AsfMessageParts::Pong { iana, oem_defined, entities, interactions }),
+                (any::<u32>(), any::<u8>().prop_filter("ping and pong have their own layout",
+                    |t| *t != ASF_MSG_TYPE_PING && *t != ASF_MSG_TYPE_PONG), any::<u8>(), vec(any::<u8>(), 0..=255))
+                    .prop_map(|(iana, msg_type, msg_tag, data)|
+                        AsfMessageParts::Other { iana, msg_type, msg_tag, data })
+            ]
+        }
+
+        #[derive(Debug, Clone)]
+        enum RmcpMessageParts {
+            Ack(u8),
+            Asf(u8, AsfMessageParts),
+            Ipmi15(u8, Ipmi15PacketParts)
+        }
+
+        impl RmcpMessageParts {
+            fn message(&self) -> RmcpMessage<'_> {
+                match self {
+                    RmcpMessageParts::Ack(seqnum) => RmcpMessage::from_ack(*seqnum),
+                    RmcpMessageParts::Asf(seqnum, asf) =>
+                        RmcpMessage::from_asf(asf.message()).with_sequence_number(*seqnum),
+                    RmcpMessageParts::Ipmi15(seqnum, packet) => RmcpMessage::from_ipmi15(packet.packet(), *seqnum)
+                }
+            }
+        }
+
+        fn rmcp_message() -> impl Strategy<Value = RmcpMessageParts> {
+            prop_oneof![
+                any::<u8>().prop_map(RmcpMessageParts::Ack),
+                (any::<u8>(), asf_message()).prop_map(|(seqnum, asf)| RmcpMessageParts::Asf(seqnum, asf)),
+                (any::<u8>(), ipmi15_packet()).prop_map(|(seqnum, packet)| RmcpMessageParts::Ipmi15(seqnum, packet))
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn ipmi_message_round_trip(parts in ipmi_message()) {
+                let msg = parts.message();
+                let mut buf = [0u8; 256];
+                prop_assert!(msg.write_to_slice(&mut buf, true).is_ok());
+                prop_assert_eq!(IpmiMessage::from_bytes(&buf[..msg.size()], true), Ok(msg));
+            }
+
+            #[test]
+            fn asf_message_round_trip(parts in asf_message()) {
+                let msg = parts.message();
+                let mut buf = [0u8; 512];
+                prop_assert!(msg.write_to_slice(&mut buf, true).is_ok());
+                prop_assert_eq!(AsfMessage::from_bytes(&buf[..msg.size()], true), Ok(msg));
+            }
+
+            #[test]
+            fn rmcp_message_round_trip(parts in rmcp_message()) {
+                let msg = parts.message();
+                let mut buf = [0u8; 512];
+                prop_assert_eq!(msg.write_into(&mut buf, true), Ok(msg.size()));
+                prop_assert_eq!(RmcpMessage::from_bytes(&buf[..msg.size()], true), Ok(msg));
+            }
+        }
+    }
 }